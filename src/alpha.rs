@@ -0,0 +1,381 @@
+//! A generic wrapper adding an alpha (opacity) channel to any color type
+
+use crate::rgb::RGB;
+use crate::{Color, ColorParseError};
+use std::fmt::Display;
+use std::ops::{Deref, DerefMut};
+
+/// A color of type `C` with an 8-bit alpha channel, where `0` is fully transparent and `255` is opaque.
+/// Conversions through [Color] use only the inner color: `as_rgb` drops the alpha and `from_rgb`
+/// creates an opaque color. Use [Alpha::convert] to change the color type while keeping the alpha.
+///
+/// The struct is `#[repr(C)]`, with `color` first and `alpha` last. Since [RGB] is itself
+/// `#[repr(C)]`, an `Alpha<RGB>` is laid out as `r`, `g`, `b`, `a` with no padding, so a
+/// `[Alpha<RGB>]` can be reinterpreted as packed RGBA pixel rows.
+/// # Examples
+/// ```
+/// use color_changer::alpha::Alpha;
+/// use color_changer::cmyk::CMYK;
+/// use color_changer::rgb::RGB;
+/// let translucent = Alpha::new(RGB::WHITE, 0x80);
+/// let cmyk: Alpha<CMYK> = translucent.convert();
+/// assert_eq!(cmyk.alpha, 0x80);
+/// assert_eq!(*cmyk, CMYK::WHITE);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct Alpha<C: Color> {
+    /// The color, without alpha
+    pub color: C,
+    /// The alpha channel
+    pub alpha: u8,
+}
+
+impl<C: Color> Alpha<C> {
+    /// Creates a color with the given alpha
+    pub const fn new(color: C, alpha: u8) -> Self {
+        Alpha { color, alpha }
+    }
+
+    /// Creates a fully opaque color
+    pub const fn opaque(color: C) -> Self {
+        Alpha::new(color, u8::MAX)
+    }
+
+    /// Converts the inner color into another color type, keeping the alpha
+    pub fn convert<U: Color>(self) -> Alpha<U> {
+        Alpha::new(self.color.into_color(), self.alpha)
+    }
+}
+
+impl Alpha<RGB> {
+    /// Returns the bytes of this color as the big-endian integer `0xRRGGBBAA`, i.e. `[r, g, b, a]`
+    pub const fn to_be_bytes(&self) -> [u8; 4] {
+        [self.color.r, self.color.g, self.color.b, self.alpha]
+    }
+
+    /// Returns the bytes of this color as the little-endian integer `0xRRGGBBAA`, i.e. `[a, b, g, r]`
+    pub const fn to_le_bytes(&self) -> [u8; 4] {
+        [self.alpha, self.color.b, self.color.g, self.color.r]
+    }
+
+    /// Creates a color from the bytes of a big-endian `0xRRGGBBAA`, i.e. `[r, g, b, a]`
+    pub const fn from_be_bytes(bytes: [u8; 4]) -> Self {
+        let [r, g, b, a] = bytes;
+        Alpha { color: RGB::new(r, g, b), alpha: a }
+    }
+
+    /// Creates a color from the bytes of a little-endian `0xRRGGBBAA`, i.e. `[a, b, g, r]`
+    pub const fn from_le_bytes(bytes: [u8; 4]) -> Self {
+        let [a, b, g, r] = bytes;
+        Alpha { color: RGB::new(r, g, b), alpha: a }
+    }
+
+    /// Returns the channels, including alpha, normalized to `[0.0, 1.0]`, as used by graphics APIs
+    pub fn to_f32_array(&self) -> [f32; 4] {
+        let [r, g, b] = self.color.to_f32_array();
+        [r, g, b, self.alpha as f32 / 255.0]
+    }
+
+    /// Creates a color from channels normalized to `[0.0, 1.0]`, clamping and rounding each
+    pub fn from_f32_array(parts: [f32; 4]) -> Self {
+        let [r, g, b, a] = parts;
+        Alpha::new(
+            RGB::from_f32_array([r, g, b]),
+            (a.clamp(0.0, 1.0) * 255.0).round() as u8,
+        )
+    }
+
+    /// Parses a CSS `rgb()` or `rgba()` function, in either the space-separated form with an
+    /// optional `/ alpha`, like `rgb(255 0 128 / 50%)`, or the legacy comma form, like
+    /// `rgba(255, 0, 128, 0.5)`. Channels are numbers in `0..=255` or percentages, and alpha is a
+    /// number in `[0.0, 1.0]` or a percentage, defaulting to opaque. As in CSS, out-of-range
+    /// values are clamped.
+    /// # Examples
+    /// ```
+    /// use color_changer::alpha::Alpha;
+    /// use color_changer::rgb::RGB;
+    /// let pink = Alpha::from_css_rgb("rgb(255 0 128 / 0.5)").unwrap();
+    /// assert_eq!(pink, Alpha::new(RGB::new(255, 0, 128), 128));
+    /// assert_eq!(Alpha::from_css_rgb("rgba(255,0,128,0.5)").unwrap(), pink);
+    /// ```
+    pub fn from_css_rgb(s: &str) -> Result<Self, ColorParseError> {
+        let s = s.trim();
+        let lower = s.to_ascii_lowercase();
+        let inner = lower.strip_prefix("rgba(")
+            .or_else(|| lower.strip_prefix("rgb("))
+            .and_then(|rest| rest.strip_suffix(')'))
+            .ok_or_else(|| ColorParseError::BadCss(format!("expected rgb(...) or rgba(...), got {:?}", s)))?;
+
+        let (channels, alpha): (Vec<&str>, Option<&str>) = if inner.contains(',') {
+            if inner.contains('/') {
+                return Err(ColorParseError::BadCss("'/' can't be mixed with commas".to_string()));
+            }
+            let mut parts: Vec<&str> = inner.split(',').map(str::trim).collect();
+            let alpha = if parts.len() == 4 { parts.pop() } else { None };
+            (parts, alpha)
+        } else {
+            let mut halves = inner.splitn(2, '/');
+            let channels = halves.next().unwrap_or("").split_whitespace().collect();
+            (channels, halves.next().map(str::trim))
+        };
+        if channels.len() != 3 {
+            // The comma form counts alpha as a fourth component; the slash form keeps it apart
+            let commas = inner.contains(',');
+            let expected = if commas && channels.len() > 3 { 4 } else { 3 };
+            let got = if commas { channels.len() + alpha.is_some() as usize } else { channels.len() };
+            return Err(ColorParseError::WrongComponentCount { expected, got });
+        }
+
+        let number = |text: &str, scale: f64, max: f64| -> Result<f64, ColorParseError> {
+            let (digits, scale) = match text.strip_suffix('%') {
+                Some(digits) => (digits, scale / 100.0),
+                None => (text, 1.0),
+            };
+            let value: f64 = digits.parse()
+                .map_err(|_| ColorParseError::BadCss(format!("{:?} isn't a number", text)))?;
+            if !value.is_finite() {
+                return Err(ColorParseError::BadCss(format!("{:?} isn't a number", text)));
+            }
+            Ok((value * scale).clamp(0.0, max))
+        };
+        let channel = |text: &str| number(text, 255.0, 255.0).map(|v| v.round() as u8);
+        let alpha = match alpha {
+            Some(text) => (number(text, 1.0, 1.0)? * 255.0).round() as u8,
+            None => 255,
+        };
+        Ok(Alpha::new(RGB::new(channel(channels[0])?, channel(channels[1])?, channel(channels[2])?), alpha))
+    }
+
+    /// Parses an alpha-first `AARRGGBB` hex string, with or without a leading `#`, as used by
+    /// Android and some Windows APIs
+    /// # Examples
+    /// ```
+    /// use color_changer::alpha::Alpha;
+    /// use color_changer::rgb::RGB;
+    /// let red = Alpha::from_hex_argb("#80FF0000").unwrap();
+    /// assert_eq!(red, Alpha::new(RGB::new(0xFF, 0, 0), 0x80));
+    /// assert_eq!(red.to_hex_argb(), "#80FF0000");
+    /// ```
+    pub fn from_hex_argb(s: &str) -> Result<Self, ColorParseError> {
+        let s = s.trim();
+        let digits = s.strip_prefix('#').unwrap_or(s);
+        if digits.chars().count() != 8 {
+            return Err(ColorParseError::WrongLength { got: digits.chars().count(), expected: &[8] });
+        }
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ColorParseError::BadInput);
+        }
+        let [a, r, g, b] = u32::from_str_radix(digits, 16)?.to_be_bytes();
+        Ok(Alpha::new(RGB::new(r, g, b), a))
+    }
+
+    /// Formats this color as an alpha-first `#AARRGGBB` hex string, the inverse of
+    /// [Alpha::from_hex_argb]
+    pub fn to_hex_argb(&self) -> String {
+        format!("#{:02X}{}", self.alpha, self.color)
+    }
+
+    /// Formats this color as an 8-digit `RRGGBBAA` hex string, the alpha-including counterpart
+    /// of [Color::as_hex], which gives only the 6 RGB digits
+    pub fn to_hex_rgba(&self) -> String {
+        format!("{}{:02X}", self.color, self.alpha)
+    }
+
+    /// Composites this color over `below` using the Porter-Duff "source over" operator.
+    /// If both are fully transparent, the result is transparent black.
+    pub fn over(&self, below: &Alpha<RGB>) -> Alpha<RGB> {
+        let top_a = self.alpha as f64 / 255.0;
+        let below_a = below.alpha as f64 / 255.0 * (1.0 - top_a);
+        let out_a = top_a + below_a;
+        if out_a == 0.0 {
+            return Alpha::new(RGB::BLACK, 0);
+        }
+        let mix = |top: u8, bottom: u8| {
+            ((top as f64 * top_a + bottom as f64 * below_a) / out_a).round() as u8
+        };
+        Alpha::new(
+            RGB::new(mix(self.r, below.r), mix(self.g, below.g), mix(self.b, below.b)),
+            (out_a * 255.0).round() as u8,
+        )
+    }
+}
+
+/// Flattens a stack of layers, ordered from the bottom-most up, by compositing each layer
+/// [over](Alpha::over) the result of the layers below it.
+/// An empty stack is transparent black.
+/// # Examples
+/// ```
+/// use color_changer::alpha::{Alpha, blend_stack};
+/// use color_changer::rgb::RGB;
+/// let base = Alpha::opaque(RGB::BLACK);
+/// let top = Alpha::new(RGB::WHITE, 0x80);
+/// assert_eq!(blend_stack(&[base, top]), Alpha::opaque(RGB::new(0x80, 0x80, 0x80)));
+/// ```
+pub fn blend_stack(layers: &[Alpha<RGB>]) -> Alpha<RGB> {
+    layers.iter().fold(Alpha::new(RGB::BLACK, 0), |below, layer| layer.over(&below))
+}
+
+impl<C: Color> Color for Alpha<C> {
+    fn into_rgb(self) -> RGB {
+        self.color.into_rgb()
+    }
+
+    fn as_rgb(&self) -> RGB {
+        self.color.as_rgb()
+    }
+
+    fn as_rgb_f64(&self) -> [f64; 3] {
+        self.color.as_rgb_f64()
+    }
+
+    fn to_rgba(&self) -> Alpha<RGB> {
+        Alpha::new(self.color.as_rgb(), self.alpha)
+    }
+
+    fn from_rgb(c: RGB) -> Self {
+        Alpha::opaque(C::from_rgb(c))
+    }
+}
+
+impl<C: Color> Display for Alpha<C> {
+    /// Displays the inner color followed by the alpha as a fraction, e.g. `FFFFFF / 0.50`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} / {:.2}", self.color, self.alpha as f64 / 255.0)
+    }
+}
+
+impl<C: Color> Deref for Alpha<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.color
+    }
+}
+
+impl<C: Color> DerefMut for Alpha<C> {
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmyk::CMYK;
+    use std::mem::{align_of, offset_of, size_of};
+
+    #[test]
+    fn layout() {
+        assert_eq!(size_of::<Alpha<RGB>>(), 4);
+        assert_eq!(align_of::<Alpha<RGB>>(), 1);
+        assert_eq!(size_of::<[Alpha<RGB>; 4]>(), 16);
+        assert_eq!(offset_of!(Alpha<RGB>, color), 0);
+        assert_eq!(offset_of!(Alpha<RGB>, alpha), 3);
+    }
+
+    #[test]
+    fn byte_order() {
+        let c = Alpha::new(RGB::new(0x11, 0x22, 0x33), 0x44);
+        assert_eq!(c.to_be_bytes(), [0x11, 0x22, 0x33, 0x44]);
+        assert_eq!(c.to_le_bytes(), [0x44, 0x33, 0x22, 0x11]);
+        assert_eq!(Alpha::from_be_bytes(c.to_be_bytes()), c);
+        assert_eq!(Alpha::from_le_bytes(c.to_le_bytes()), c);
+        assert_eq!(0x11223344u32.to_be_bytes(), c.to_be_bytes());
+        assert_eq!(0x11223344u32.to_le_bytes(), c.to_le_bytes());
+    }
+
+    #[test]
+    fn css_rgb() {
+        let pink = Alpha::new(RGB::new(255, 0, 128), 128);
+        for input in &[
+            "rgb(255 0 128 / 0.5)",
+            "rgb(255 0 128 / 50%)",
+            "rgba(255,0,128,0.5)",
+            " RGBA( 255 , 0 , 128 , 50% ) ",
+            "rgb(100% 0% 50.2% / .5)",
+        ] {
+            assert_eq!(Alpha::from_css_rgb(input).unwrap(), pink, "{}", input);
+        }
+        assert_eq!(pink.alpha, 128);
+
+        let opaque = Alpha::opaque(RGB::new(255, 0, 128));
+        assert_eq!(Alpha::from_css_rgb("rgb(255, 0, 128)").unwrap(), opaque);
+        assert_eq!(Alpha::from_css_rgb("rgb(255 0 128)").unwrap(), opaque);
+        assert_eq!(Alpha::from_css_rgb("rgb(300 -5 128 / 2)").unwrap(), opaque);
+
+        assert!(matches!(
+            Alpha::from_css_rgb("rgb(255 0 / 0.5)"),
+            Err(ColorParseError::WrongComponentCount { expected: 3, got: 2 })
+        ));
+        assert!(matches!(
+            Alpha::from_css_rgb("rgba(1, 2, 3, 4, 5)"),
+            Err(ColorParseError::WrongComponentCount { expected: 4, got: 5 })
+        ));
+        for bad in &["hsl(0 0% 0%)", "rgb(255 0 128", "rgb(1, 2, 3 / 0.5)", "rgb(red 0 0)", "rgb(NaN 0 0)"] {
+            assert!(matches!(Alpha::from_css_rgb(bad), Err(ColorParseError::BadCss(_))), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn argb_hex() {
+        let red = Alpha::from_hex_argb("#80FF0000").unwrap();
+        assert_eq!(red.color, RGB::new(0xFF, 0, 0));
+        assert_eq!(red.alpha, 0x80);
+        assert_eq!(red.to_hex_argb(), "#80FF0000");
+        assert_eq!(Alpha::from_hex_argb(&red.to_hex_argb()).unwrap(), red);
+        assert_eq!(Alpha::from_hex_argb("ff123456").unwrap(), Alpha::opaque(RGB::new(0x12, 0x34, 0x56)));
+
+        let error = Alpha::from_hex_argb("#FF0000").unwrap_err();
+        assert!(matches!(error, ColorParseError::WrongLength { got: 6, expected: [8] }));
+        assert_eq!(error.to_string(), "Expected 8 hex digits after an optional '#', but got 6");
+        assert!(matches!(Alpha::from_hex_argb("+80FF000"), Err(ColorParseError::BadInput)));
+    }
+
+    #[test]
+    fn alpha_conversions() {
+        let rgb = Alpha::new(RGB::new(0xED, 0xBB, 0xF3), 0x40);
+        assert_eq!(rgb.to_string(), "EDBBF3 / 0.25");
+        assert_eq!(rgb.r, 0xED);
+        assert_eq!(rgb.as_rgb(), rgb.color);
+
+        let cmyk: Alpha<CMYK> = rgb.convert();
+        assert_eq!(cmyk.alpha, 0x40);
+        assert_eq!(cmyk.color, CMYK::from_rgb(rgb.color));
+        assert_eq!(cmyk.convert::<RGB>(), rgb);
+
+        let opaque: Alpha<CMYK> = rgb.into_color();
+        assert_eq!(opaque, Alpha::opaque(cmyk.color));
+
+        let mut c = Alpha::new(RGB::BLACK, 0);
+        assert_eq!(c.to_f32_array(), [0.0; 4]);
+        c.g = 0xFF;
+        assert_eq!(c.color, RGB::new(0, 0xFF, 0));
+    }
+
+    #[test]
+    fn f32_arrays() {
+        assert_eq!(Alpha::opaque(RGB::WHITE).to_f32_array(), [1.0; 4]);
+        let c = Alpha::new(RGB::new(0x12, 0x80, 0xFE), 0x33);
+        assert_eq!(Alpha::from_f32_array(c.to_f32_array()), c);
+        assert_eq!(Alpha::from_f32_array([0.0, 0.0, 0.0, 2.0]), Alpha::opaque(RGB::BLACK));
+    }
+
+    #[test]
+    fn blend_stack_matches_pairwise_over() {
+        let bottom = Alpha::new(RGB::new(0xFF, 0, 0), 0x80);
+        let middle = Alpha::new(RGB::new(0, 0xFF, 0), 0x60);
+        let top = Alpha::new(RGB::new(0, 0, 0xFF), 0x40);
+        let expected = top.over(&middle.over(&bottom));
+        assert_eq!(blend_stack(&[bottom, middle, top]), expected);
+        assert!(expected.alpha > bottom.alpha);
+        assert!(expected.r > 0 && expected.g > 0 && expected.b > 0);
+
+        assert_eq!(blend_stack(&[]), Alpha::new(RGB::BLACK, 0));
+        assert_eq!(blend_stack(&[bottom]), bottom);
+        let opaque = Alpha::opaque(RGB::WHITE);
+        assert_eq!(opaque.over(&bottom), opaque);
+        let clear = Alpha::new(RGB::WHITE, 0);
+        assert_eq!(clear.over(&clear), Alpha::new(RGB::BLACK, 0));
+    }
+}
@@ -0,0 +1,84 @@
+//! Conversions to ANSI terminal colors
+
+use crate::rgb::RGB;
+use crate::Color;
+
+/// The standard 16 ANSI colors, using xterm's default values, in order: black, red, green, yellow,
+/// blue, magenta, cyan and white, followed by the bright variants of each
+pub const ANSI_16: [RGB; 16] = [
+    RGB::new(0x00, 0x00, 0x00),
+    RGB::new(0xCD, 0x00, 0x00),
+    RGB::new(0x00, 0xCD, 0x00),
+    RGB::new(0xCD, 0xCD, 0x00),
+    RGB::new(0x00, 0x00, 0xEE),
+    RGB::new(0xCD, 0x00, 0xCD),
+    RGB::new(0x00, 0xCD, 0xCD),
+    RGB::new(0xE5, 0xE5, 0xE5),
+    RGB::new(0x7F, 0x7F, 0x7F),
+    RGB::new(0xFF, 0x00, 0x00),
+    RGB::new(0x00, 0xFF, 0x00),
+    RGB::new(0xFF, 0xFF, 0x00),
+    RGB::new(0x5C, 0x5C, 0xFF),
+    RGB::new(0xFF, 0x00, 0xFF),
+    RGB::new(0x00, 0xFF, 0xFF),
+    RGB::new(0xFF, 0xFF, 0xFF),
+];
+
+impl RGB {
+    /// Returns the SGR foreground code of the perceptually nearest (by CIEDE2000) of the [ANSI_16]
+    /// colors: `30`–`37` for the normal colors and `90`–`97` for the bright ones.
+    /// Add `10` to get the matching background code.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::new(0xFF, 0, 0).to_ansi16(), 91);
+    /// assert_eq!(RGB::new(0x10, 0x10, 0x10).to_ansi16(), 30);
+    /// ```
+    pub fn to_ansi16(&self) -> u8 {
+        let nearest = ANSI_16.iter()
+            .map(|c| self.distance_to(c))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(i, _)| i as u8)
+            .unwrap();
+        if nearest < 8 { 30 + nearest } else { 90 + nearest - 8 }
+    }
+
+    /// Returns a swatch of `width` spaces with this color as a 24-bit ANSI background,
+    /// followed by a reset, for printing color previews in terminals
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::new(255, 0, 128).swatch(2), "\x1b[48;2;255;0;128m  \x1b[0m");
+    /// ```
+    pub fn swatch(&self, width: usize) -> String {
+        format!("\x1b[48;2;{};{};{}m{}\x1b[0m", self.r, self.g, self.b, " ".repeat(width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansi16() {
+        assert_eq!(RGB::new(0xFF, 0, 0).to_ansi16(), 91);
+        assert_eq!(RGB::new(0xC0, 0x10, 0x10).to_ansi16(), 31);
+        assert_eq!(RGB::WHITE.to_ansi16(), 97);
+        assert_eq!(RGB::BLACK.to_ansi16(), 30);
+        assert_eq!(RGB::new(0x80, 0x80, 0x80).to_ansi16(), 90);
+        for (i, c) in ANSI_16.iter().enumerate() {
+            let expected = if i < 8 { 30 + i } else { 82 + i };
+            assert_eq!(c.to_ansi16() as usize, expected);
+        }
+    }
+
+    #[test]
+    fn swatch() {
+        let swatch = RGB::new(0x12, 0x34, 0x56).swatch(4);
+        assert!(swatch.starts_with("\x1b[48;2;18;52;86m"));
+        assert!(swatch.ends_with("\x1b[0m"));
+        assert_eq!(swatch.matches(' ').count(), 4);
+        assert_eq!(RGB::BLACK.swatch(0), "\x1b[48;2;0;0;0m\x1b[0m");
+    }
+}
@@ -0,0 +1,174 @@
+//! CIE XYZ and L*a*b* conversions used internally for perceptual calculations.
+//! All conversions assume sRGB primaries and the D65 reference white.
+
+use crate::hue::normalize_hue;
+use crate::rgb::RGB;
+
+/// A color in CIE L*a*b*, as `[L, a, b]`
+pub(crate) type Lab = [f64; 3];
+
+/// The D65 reference white in XYZ, normalized to `Y = 1.0`
+const D65: [f64; 3] = [0.95047, 1.0, 1.08883];
+
+const EPSILON: f64 = 216.0 / 24389.0;
+const KAPPA: f64 = 24389.0 / 27.0;
+
+/// Converts an RGB-255 color into CIE XYZ, with `Y` in `[0.0, 1.0]`
+pub(crate) fn rgb_to_xyz(c: RGB) -> [f64; 3] {
+    let [r, g, b] = c.as_linear();
+    [
+        0.4124564 * r + 0.3575761 * g + 0.1804375 * b,
+        0.2126729 * r + 0.7151522 * g + 0.0721750 * b,
+        0.0193339 * r + 0.1191920 * g + 0.9503041 * b,
+    ]
+}
+
+/// Converts an RGB-255 color into CIE L*a*b*
+pub(crate) fn rgb_to_lab(c: RGB) -> Lab {
+    let xyz = rgb_to_xyz(c);
+    let f = |t: f64| {
+        if t > EPSILON {
+            t.cbrt()
+        } else {
+            (KAPPA * t + 16.0) / 116.0
+        }
+    };
+    let fx = f(xyz[0] / D65[0]);
+    let fy = f(xyz[1] / D65[1]);
+    let fz = f(xyz[2] / D65[2]);
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Converts a CIE L*a*b* color into RGB-255. Out-of-gamut colors are clamped per channel.
+pub(crate) fn lab_to_rgb(lab: Lab) -> RGB {
+    let [l, a, b] = lab;
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+    let f_inv = |t: f64| {
+        if t.powi(3) > EPSILON {
+            t.powi(3)
+        } else {
+            (116.0 * t - 16.0) / KAPPA
+        }
+    };
+    let y = if l > KAPPA * EPSILON { fy.powi(3) } else { l / KAPPA };
+    xyz_to_rgb([f_inv(fx) * D65[0], y * D65[1], f_inv(fz) * D65[2]])
+}
+
+/// Converts a CIE XYZ color into RGB-255. Out-of-gamut colors are clamped per channel.
+pub(crate) fn xyz_to_rgb(xyz: [f64; 3]) -> RGB {
+    RGB::from_linear(xyz_to_linear_srgb(xyz))
+}
+
+/// Converts a CIE XYZ color into linear-light sRGB, without clamping, so out-of-gamut colors
+/// have components outside `[0.0, 1.0]`
+pub(crate) fn xyz_to_linear_srgb(xyz: [f64; 3]) -> [f64; 3] {
+    let [x, y, z] = xyz;
+    [
+        3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+        -0.9692660 * x + 1.8760108 * y + 0.0415560 * z,
+        0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+    ]
+}
+
+/// Computes the CIEDE2000 color difference between two L*a*b* colors
+pub(crate) fn delta_e_2000(lab1: Lab, lab2: Lab) -> f64 {
+    let [l1, a1, b1] = lab1;
+    let [l2, a2, b2] = lab2;
+
+    let c_bar = ((a1 * a1 + b1 * b1).sqrt() + (a2 * a2 + b2 * b2).sqrt()) / 2.0;
+    let c_bar7 = c_bar.powi(7);
+    let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + 25f64.powi(7))).sqrt());
+
+    let a1p = (1.0 + g) * a1;
+    let a2p = (1.0 + g) * a2;
+    let c1p = (a1p * a1p + b1 * b1).sqrt();
+    let c2p = (a2p * a2p + b2 * b2).sqrt();
+
+    let hue = |b: f64, a: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            normalize_hue(b.atan2(a).to_degrees())
+        }
+    };
+    let h1p = hue(b1, a1p);
+    let h2p = hue(b2, a2p);
+
+    let dl = l2 - l1;
+    let dc = c2p - c1p;
+    let dh = if c1p * c2p == 0.0 {
+        0.0
+    } else if (h2p - h1p).abs() <= 180.0 {
+        h2p - h1p
+    } else if h2p - h1p > 180.0 {
+        h2p - h1p - 360.0
+    } else {
+        h2p - h1p + 360.0
+    };
+    let dh = 2.0 * (c1p * c2p).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar_p = (c1p + c2p) / 2.0;
+    let h_bar_p = if c1p * c2p == 0.0 {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar_p - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar_p).to_radians().cos()
+        + 0.32 * (3.0 * h_bar_p + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar_p - 63.0).to_radians().cos();
+    let d_theta = 30.0 * (-((h_bar_p - 275.0) / 25.0).powi(2)).exp();
+    let c_bar_p7 = c_bar_p.powi(7);
+    let r_c = 2.0 * (c_bar_p7 / (c_bar_p7 + 25f64.powi(7))).sqrt();
+    let l_term = (l_bar - 50.0).powi(2);
+    let s_l = 1.0 + 0.015 * l_term / (20.0 + l_term).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar_p;
+    let s_h = 1.0 + 0.015 * c_bar_p * t;
+    let r_t = -(2.0 * d_theta).to_radians().sin() * r_c;
+
+    let dl = dl / s_l;
+    let dc = dc / s_c;
+    let dh = dh / s_h;
+    (dl * dl + dc * dc + dh * dh + r_t * dc * dh).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ciede2000_reference_pairs() {
+        // Pairs from Sharma, Wu & Dalal's CIEDE2000 test data
+        let pairs = [
+            ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+            ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+            ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+            ([22.7233, 20.0904, -46.6940], [23.0331, 14.9730, -42.5619], 2.0373),
+        ];
+        for &(a, b, expected) in &pairs {
+            assert!((delta_e_2000(a, b) - expected).abs() < 1e-4, "{:?} {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn lab_roundtrip() {
+        for &c in &[RGB::BLACK, RGB::WHITE, RGB::new(0xED, 0xBB, 0xF3), RGB::new(0xFF, 0, 0), RGB::new(1, 2, 3)] {
+            assert_eq!(lab_to_rgb(rgb_to_lab(c)), c);
+        }
+    }
+
+    #[test]
+    fn white_is_l_100() {
+        let [l, a, b] = rgb_to_lab(RGB::WHITE);
+        assert!((l - 100.0).abs() < 1e-3);
+        assert!(a.abs() < 1e-2 && b.abs() < 1e-2);
+    }
+}
@@ -2,7 +2,8 @@
 //! Note that this results in potentially lossy conversions to RGB-255 color space.
 
 use crate::rgb::RGB;
-use crate::Color;
+use crate::{Color, ColorParseError, RoundingMode};
+use std::cmp::Ordering;
 use std::fmt::Display;
 
 /// A color in the CMYK color space, with u16 representing each component internally.
@@ -18,16 +19,43 @@ pub struct CMYK {
     pub k: u16,
 }
 
+/// Controls how much black (K) ink [CMYK::from_rgb_with_k] generates
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum KStrategy {
+    /// Use as much black as possible, as [CMYK::from_rgb] does
+    Max,
+    /// Use no black at all, producing a pure CMY color with `k = 0`
+    None,
+    /// Use a fixed amount of black in `[0.0, 1.0]`, with the remaining darkness made up by CMY.
+    /// Colors lighter than this amount of black allows are clamped.
+    Fixed(f64),
+}
+
+/// A simple description of a printer's CMYK gamut, used by [CMYK::snap_to_gamut].
+/// Ink amounts use the `[0.0, 1.0]` float scale per channel, so a total ink limit of 300% is `3.0`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GamutProfile {
+    /// The maximum total ink coverage, summed over all four channels
+    pub max_total_ink: f64,
+    /// The maximum coverage of any single channel
+    pub max_channel: f64,
+}
+
+impl Default for GamutProfile {
+    /// A 300% total ink limit with no per-channel limit, typical for coated stock
+    fn default() -> Self {
+        GamutProfile { max_total_ink: 3.0, max_channel: 1.0 }
+    }
+}
+
 impl Color for CMYK {
     fn as_rgb(&self) -> RGB {
+        self.as_rgb_with_rounding(RoundingMode::HalfUp)
+    }
+
+    fn as_rgb_f64(&self) -> [f64; 3] {
         let [c, m, y, k] = self.as_parts();
-        let r = 255.0 * (1.0 - c) * (1.0 - k);
-        let g = 255.0 * (1.0 - m) * (1.0 - k);
-        let b = 255.0 * (1.0 - y) * (1.0 - k);
-        let r = r.round() as u8;
-        let g = g.round() as u8;
-        let b = b.round() as u8;
-        RGB { r, g, b }
+        [c, m, y].map(|ink| 255.0 * (1.0 - ink) * (1.0 - k))
     }
 
     fn from_rgb(c: RGB) -> Self {
@@ -61,7 +89,7 @@ impl Display for CMYK {
 impl CMYK {
 
     /// The color black, as CMYK
-    pub const BLACK: CMYK = CMYK::from_parts([0, 0, 0, std::u16::MAX]);
+    pub const BLACK: CMYK = CMYK::from_parts([0, 0, 0, u16::MAX]);
 
     /// The color white, as CMYK
     pub const WHITE: CMYK = CMYK::from_parts([0, 0, 0, 0]);
@@ -79,6 +107,134 @@ impl CMYK {
         CMYK::from_parts(parts)
     }
 
+    /// Converts into RGB-255 like [Color::as_rgb], but rounding channels with `mode`
+    pub fn as_rgb_with_rounding(&self, mode: RoundingMode) -> RGB {
+        let [r, g, b] = self.as_rgb_f64();
+        RGB::from_floats_with_rounding(r, g, b, mode)
+    }
+
+    /// Converts from RGB, using `k_strategy` to decide how much black ink to generate.
+    /// # Panics
+    /// Panics if a [KStrategy::Fixed] amount is not in `[0.0, 1.0]`
+    /// # Examples
+    /// ```
+    /// use color_changer::cmyk::{CMYK, KStrategy};
+    /// use color_changer::rgb::RGB;
+    /// let cmy = CMYK::from_rgb_with_k(RGB::BLACK, KStrategy::None);
+    /// assert_eq!(cmy, CMYK::new(1.0, 1.0, 1.0, 0.0));
+    /// ```
+    pub fn from_rgb_with_k(c: RGB, k_strategy: KStrategy) -> Self {
+        let k = match k_strategy {
+            KStrategy::Max => return CMYK::from_rgb(c),
+            KStrategy::None => 0.0,
+            KStrategy::Fixed(k) => {
+                assert!(CMYK::valid_cmyk_float(k));
+                k
+            }
+        };
+        if k == 1.0 {
+            return CMYK::BLACK;
+        }
+        let ink = |v: u8| ((1.0 - v as f64 / 255.0 - k) / (1.0 - k)).clamp(0.0, 1.0);
+        CMYK::new(ink(c.r), ink(c.g), ink(c.b), k)
+    }
+
+    /// Adjusts this color to fit within `profile`, returning the adjusted color and whether any
+    /// adjustment was needed. Each channel is clamped to the channel limit first; if the total
+    /// ink is still over the limit, cyan, magenta and yellow are reduced proportionally, keeping
+    /// black, which best preserves the color's darkness.
+    /// # Examples
+    /// ```
+    /// use color_changer::cmyk::{CMYK, GamutProfile};
+    /// let rich_black = CMYK::new(1.0, 1.0, 1.0, 1.0);
+    /// let (snapped, clamped) = rich_black.snap_to_gamut(GamutProfile::default());
+    /// assert!(clamped);
+    /// assert_eq!(snapped, CMYK::new(2.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0, 1.0));
+    /// ```
+    pub fn snap_to_gamut(&self, profile: GamutProfile) -> (CMYK, bool) {
+        let max_channel = profile.max_channel.clamp(0.0, 1.0);
+        let max_total = profile.max_total_ink.max(0.0);
+        let [c, m, y, k] = self.as_parts().map(|f| f.min(max_channel));
+        let k = k.min(max_total);
+        let cmy = c + m + y;
+        let scale = if cmy + k > max_total { (max_total - k) / cmy } else { 1.0 };
+        let snapped = CMYK::new(c * scale, m * scale, y * scale, k);
+        (snapped, snapped != *self)
+    }
+
+    /// Simulates printing `other` on top of this color. Overprinted inks accumulate, so each
+    /// channel takes the larger of the two coverages.
+    /// # Examples
+    /// ```
+    /// use color_changer::cmyk::CMYK;
+    /// let blue = CMYK::new(1.0, 0.0, 0.0, 0.0).overprint(&CMYK::new(0.0, 1.0, 0.0, 0.0));
+    /// assert_eq!(blue, CMYK::new(1.0, 1.0, 0.0, 0.0));
+    /// ```
+    pub fn overprint(&self, other: &CMYK) -> CMYK {
+        CMYK {
+            c: self.c.max(other.c),
+            m: self.m.max(other.m),
+            y: self.y.max(other.y),
+            k: self.k.max(other.k),
+        }
+    }
+
+    /// Like [CMYK::overprint], but [snaps](CMYK::snap_to_gamut) the result into `profile`, so
+    /// the combined inks respect the press's ink limit
+    pub fn overprint_limited(&self, other: &CMYK, profile: GamutProfile) -> CMYK {
+        self.overprint(other).snap_to_gamut(profile).0
+    }
+
+    /// Approximates how this color prints on a press with dot gain, where ink spreads and tints
+    /// print darker than specified. Each channel `d` becomes `d + 4 * gain * d * (1 - d)`, so
+    /// `gain` is the increase at a 50% tint (a typical press is around `0.15`) while solid and
+    /// empty channels are unchanged. A non-finite `gain` saturates every partial tint: infinite
+    /// gain fills it to solid, negative infinity empties it, and `NaN` changes nothing.
+    ///
+    /// This is a rough approximation for previews, not a substitute for an ICC profile.
+    pub fn with_dot_gain(&self, gain: f64) -> CMYK {
+        let [c, m, y, k] = self.as_parts().map(|d| {
+            let gained = d + 4.0 * gain * d * (1.0 - d);
+            // A non-finite gain on a solid or empty channel gives inf * 0 = NaN; keep the channel
+            if gained.is_nan() { d } else { gained.clamp(0.0, 1.0) }
+        });
+        CMYK::new(c, m, y, k)
+    }
+
+    /// Converts from RGB like [Color::from_rgb], except that colors which are
+    /// [near black](RGB::is_near_black) within `tolerance` become exactly [CMYK::BLACK].
+    /// Otherwise a color like `RGB::new(1, 0, 0)` converts to nearly full black plus full
+    /// magenta and yellow, which is rarely wanted in print.
+    pub fn from_rgb_near_black(c: RGB, tolerance: u8) -> Self {
+        if c.is_near_black(tolerance) {
+            CMYK::BLACK
+        } else {
+            CMYK::from_rgb(c)
+        }
+    }
+
+    /// Converts a slice of RGB pixels to CMYK, giving exactly the same results as converting each
+    /// with [Color::from_rgb]. Runs of identical pixels, common in images, are converted once.
+    pub fn from_rgb_slice(pixels: &[RGB]) -> Vec<CMYK> {
+        convert_runs(pixels, CMYK::from_rgb)
+    }
+
+    /// Converts a slice of CMYK colors to RGB, giving exactly the same results as converting each
+    /// with [Color::as_rgb]. Runs of identical colors are converted once.
+    pub fn to_rgb_slice(colors: &[CMYK]) -> Vec<RGB> {
+        convert_runs(colors, |c| c.as_rgb())
+    }
+
+    /// Returns the total ink coverage in raw u16 units, summed over all four components
+    pub fn total_ink(&self) -> u32 {
+        self.as_int_parts().iter().map(|&i| i as u32).sum()
+    }
+
+    /// Compares two colors by [total ink](CMYK::total_ink) coverage
+    pub fn cmp_by_ink(&self, other: &CMYK) -> Ordering {
+        self.total_ink().cmp(&other.total_ink())
+    }
+
     /// Creates a CMYK object from the raw u16 components.
     /// This is used in [CMYK::new()]
     pub const fn from_parts(parts: [u16; 4]) -> Self {
@@ -86,15 +242,67 @@ impl CMYK {
         CMYK {c, m, y, k}
     }
 
+    /// Creates a CMYK object from the raw u16 components like [CMYK::from_parts], but fails with
+    /// [ColorParseError::InkLimitExceeded] if `max_ink` is given and the total ink, summed over
+    /// the float components, is over it. For example, a 300% limit is `Some(3.0)`.
+    /// # Examples
+    /// ```
+    /// use color_changer::cmyk::CMYK;
+    /// let max = u16::MAX;
+    /// assert!(CMYK::from_parts_checked([max, max, 0, 0], Some(3.0)).is_ok());
+    /// assert!(CMYK::from_parts_checked([max, max, max, max], Some(3.0)).is_err());
+    /// ```
+    pub fn from_parts_checked(parts: [u16; 4], max_ink: Option<f64>) -> Result<Self, ColorParseError> {
+        let color = CMYK::from_parts(parts);
+        if let Some(max) = max_ink {
+            let total = color.as_parts().iter().sum::<f64>();
+            if total > max {
+                return Err(ColorParseError::InkLimitExceeded { total, max });
+            }
+        }
+        Ok(color)
+    }
+
+    /// Formats as integer percentages separated by colons, e.g. `0:23:0:5`, as used by many
+    /// print tools. Each percentage is rounded to the nearest integer.
+    /// # Examples
+    /// ```
+    /// use color_changer::cmyk::CMYK;
+    /// assert_eq!(CMYK::new(0.0, 0.234, 0.0, 0.05).to_colon_percent(), "0:23:0:5");
+    /// ```
+    pub fn to_colon_percent(&self) -> String {
+        let [c, m, y, k] = self.as_parts().map(|f| (f * 100.0).round() as u8);
+        format!("{}:{}:{}:{}", c, m, y, k)
+    }
+
+    /// Parses the colon-separated integer percent format produced by [CMYK::to_colon_percent].
+    /// Each component must be an integer in `0..=100`.
+    pub fn from_colon_percent(s: &str) -> Result<Self, ColorParseError> {
+        let parts: Vec<&str> = s.trim().split(':').collect();
+        if parts.len() != 4 {
+            return Err(ColorParseError::WrongComponentCount { expected: 4, got: parts.len() });
+        }
+        let mut floats = [0.0; 4];
+        for ((part, name), float) in parts.iter().zip(['c', 'm', 'y', 'k']).zip(floats.iter_mut()) {
+            let value: i64 = part.trim().parse()?;
+            if !(0..=100).contains(&value) {
+                return Err(ColorParseError::OutOfRange { component: name, value });
+            }
+            *float = value as f64 / 100.0;
+        }
+        let [c, m, y, k] = floats;
+        Ok(CMYK::new(c, m, y, k))
+    }
+
     /// Converts a u16 into the traditional `[0.0, 1.0]` float representation
     pub(crate) fn conv_to_float(i: u16) -> f64 {
-        (i as f64) / std::u16::MAX as f64
+        (i as f64) / u16::MAX as f64
     }
 
     /// Returns whether or not the given float is in `[0.0, 1.0]`
     pub fn valid_cmyk_float(f: impl Into<f64>) -> bool {
         let f = f.into();
-        0.0 <= f && f <= 1.0
+        (0.0..=1.0).contains(&f)
     }
 
     /// Converts a float from `[0.0, 1.0]` into the CMYK u16 representation
@@ -102,7 +310,7 @@ impl CMYK {
     /// Panics if `f` is not in the aforementioned range.
     pub(crate) fn conv_to_int(f: f64) -> u16 {
         assert!(CMYK::valid_cmyk_float(f));
-        (f * std::u16::MAX as f64).round() as u16
+        (f * u16::MAX as f64).round() as u16
     }
 
     /// Returns the raw integer parts of the CMYK representation
@@ -119,6 +327,27 @@ impl CMYK {
     }
 }
 
+/// Sorts `colors` from least to most total ink coverage, keeping the order of equal colors.
+/// See [CMYK::cmp_by_ink].
+pub fn sort_by_ink(colors: &mut [CMYK]) {
+    colors.sort_by(CMYK::cmp_by_ink);
+}
+
+/// Maps `f` over `items`, reusing the previous result while the input repeats
+fn convert_runs<T: Copy + PartialEq, U: Copy>(items: &[T], f: impl Fn(T) -> U) -> Vec<U> {
+    let mut last: Option<(T, U)> = None;
+    items.iter()
+        .map(|&item| match last {
+            Some((prev, out)) if prev == item => out,
+            _ => {
+                let out = f(item);
+                last = Some((item, out));
+                out
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,4 +371,220 @@ mod tests {
         assert_eq!("(0.02,0.23,0.00,0.05)", sg.into_color::<CMYK>().to_string());
         assert_eq!(sg, sg.into_color::<CMYK>().into_rgb())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn k_strategies() {
+        let dark = RGB::new(0x40, 0x20, 0x10);
+        let max = CMYK::from_rgb_with_k(dark, KStrategy::Max);
+        assert_eq!(max, CMYK::from_rgb(dark));
+        assert_eq!(max.c, 0);
+
+        let none = CMYK::from_rgb_with_k(dark, KStrategy::None);
+        assert_eq!(none.k, 0);
+        assert!(none.c > max.c && none.m > max.m && none.y > max.y);
+
+        let fixed = CMYK::from_rgb_with_k(dark, KStrategy::Fixed(0.5));
+        assert_eq!(fixed.k, CMYK::conv_to_int(0.5));
+        assert!(none.k < fixed.k && fixed.k < max.k);
+
+        for strategy in [KStrategy::Max, KStrategy::None, KStrategy::Fixed(0.5)] {
+            let back = CMYK::from_rgb_with_k(dark, strategy).as_rgb();
+            for (a, b) in back.as_parts().iter().zip(dark.as_parts().iter()) {
+                assert!(a.abs_diff(*b) <= 1, "{:?} {:?}", strategy, back);
+            }
+        }
+    }
+
+    #[test]
+    fn overprint() {
+        let cyan = CMYK::new(1.0, 0.0, 0.0, 0.0);
+        let magenta = CMYK::new(0.0, 1.0, 0.0, 0.0);
+        let both = cyan.overprint(&magenta);
+        assert_eq!(both, CMYK::new(1.0, 1.0, 0.0, 0.0));
+        assert_eq!(both, magenta.overprint(&cyan));
+        assert_eq!(cyan.overprint(&CMYK::WHITE), cyan);
+
+        let heavy = CMYK::new(0.9, 0.9, 0.0, 0.4).overprint(&CMYK::new(0.2, 0.0, 0.9, 0.6));
+        let limit = GamutProfile { max_total_ink: 2.5, ..GamutProfile::default() };
+        let limited = heavy.overprint_limited(&CMYK::WHITE, limit);
+        assert!(limited.as_parts().iter().sum::<f64>() <= 2.5 + 1e-4);
+        assert_eq!(limited.k, heavy.k);
+    }
+
+    #[test]
+    fn snap_to_gamut() {
+        let profile = GamutProfile { max_total_ink: 2.4, max_channel: 0.9 };
+        let heavy = CMYK::new(0.8, 0.9, 0.8, 0.5);
+        let (snapped, clamped) = heavy.snap_to_gamut(profile);
+        assert!(clamped);
+        assert_eq!(snapped.k, heavy.k);
+        assert!(snapped.as_parts().iter().sum::<f64>() <= 2.4 + 1e-4);
+        assert!(snapped.m > snapped.c);
+
+        let (snapped, clamped) = CMYK::new(0.0, 0.95, 0.0, 0.0).snap_to_gamut(profile);
+        assert!(clamped);
+        assert_eq!(snapped, CMYK::new(0.0, 0.9, 0.0, 0.0));
+
+        let light = CMYK::new(0.2, 0.3, 0.1, 0.1);
+        assert_eq!(light.snap_to_gamut(profile), (light, false));
+        assert_eq!(CMYK::BLACK.snap_to_gamut(GamutProfile::default()), (CMYK::BLACK, false));
+    }
+
+    #[test]
+    fn as_rgb_rounding() {
+        // Each channel comes out at about 127.7
+        let c = CMYK::new(0.0, 0.0, 0.0, 1.0 - 127.7 / 255.0);
+        assert_eq!(c.as_rgb_with_rounding(RoundingMode::Truncate), RGB::new(127, 127, 127));
+        assert_eq!(c.as_rgb_with_rounding(RoundingMode::HalfUp), RGB::new(128, 128, 128));
+        assert_eq!(c.as_rgb_with_rounding(RoundingMode::HalfEven), RGB::new(128, 128, 128));
+        assert_eq!(c.as_rgb(), RGB::new(128, 128, 128));
+    }
+
+    #[test]
+    fn dot_gain() {
+        let c = CMYK::new(0.2, 0.5, 0.0, 1.0);
+        assert_eq!(c.with_dot_gain(0.0), c);
+
+        let gained = c.with_dot_gain(0.15);
+        let [gc, gm, gy, gk] = gained.as_parts();
+        assert!((gm - 0.65).abs() < 1e-4);
+        assert!(gc > 0.2 && gc < gm);
+        assert_eq!(gy, 0.0);
+        assert_eq!(gk, 1.0);
+
+        let midtone = CMYK::new(0.0, 0.0, 0.0, 0.5);
+        assert!(midtone.with_dot_gain(0.2).as_rgb().r < midtone.as_rgb().r);
+
+        assert_eq!(c.with_dot_gain(f64::INFINITY), CMYK::new(1.0, 1.0, 0.0, 1.0));
+        assert_eq!(c.with_dot_gain(f64::NEG_INFINITY), CMYK::new(0.0, 0.0, 0.0, 1.0));
+        assert_eq!(c.with_dot_gain(f64::NAN), c);
+    }
+
+    #[test]
+    fn rgb_roundtrip_is_lossless() {
+        // u16 components are precise enough that every RGB-255 color survives RGB -> CMYK -> RGB
+        for r in (0..=255u8).step_by(3) {
+            for g in (0..=255u8).step_by(5) {
+                for b in 0..=255u8 {
+                    let c = RGB::new(r, g, b);
+                    assert_eq!(CMYK::from_rgb(c).as_rgb(), c);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rgb_roundtrip_harness() {
+        crate::assert_color_roundtrip!(CMYK, 0, [
+            RGB::BLACK,
+            RGB::WHITE,
+            RGB::new(0xED, 0xBB, 0xF3),
+            RGB::new(1, 2, 3),
+            RGB::new(0xFE, 0xFF, 0xFD),
+        ]);
+    }
+
+    #[test]
+    fn as_rgb_f64() {
+        let c = CMYK::new(0.0, 0.0, 0.0, 1.0 - 127.7 / 255.0);
+        let [r, g, b] = c.as_rgb_f64();
+        assert!((r - 127.7).abs() < 0.01);
+        assert_eq!(r, g);
+        assert_eq!(g, b);
+        assert!(r.fract() != 0.0);
+        assert_eq!(c.as_rgb(), RGB::new(128, 128, 128));
+        assert_eq!(CMYK::WHITE.as_rgb_f64(), [255.0; 3]);
+    }
+
+    #[test]
+    fn from_parts_checked() {
+        let half = u16::MAX / 2;
+        let parts = [half, half, half, u16::MAX];
+        assert_eq!(CMYK::from_parts_checked(parts, None).unwrap(), CMYK::from_parts(parts));
+        assert_eq!(CMYK::from_parts_checked(parts, Some(2.6)).unwrap(), CMYK::from_parts(parts));
+        match CMYK::from_parts_checked(parts, Some(2.4)) {
+            Err(ColorParseError::InkLimitExceeded { total, max }) => {
+                assert!((total - 2.5).abs() < 1e-3);
+                assert_eq!(max, 2.4);
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn colon_percent() {
+        let c = CMYK::from_colon_percent("0:23:0:5").unwrap();
+        assert_eq!(c, CMYK::new(0.0, 0.23, 0.0, 0.05));
+        assert_eq!(c.to_colon_percent(), "0:23:0:5");
+        for s in &["100:100:100:100", "0:0:0:0", "12:34:56:78"] {
+            assert_eq!(&CMYK::from_colon_percent(s).unwrap().to_colon_percent(), s);
+        }
+        assert_eq!(CMYK::BLACK.to_colon_percent(), "0:0:0:100");
+
+        assert!(matches!(
+            CMYK::from_colon_percent("101:0:0:0"),
+            Err(ColorParseError::OutOfRange { component: 'c', value: 101 })
+        ));
+        assert!(matches!(
+            CMYK::from_colon_percent("0:0:0:-1"),
+            Err(ColorParseError::OutOfRange { component: 'k', value: -1 })
+        ));
+        assert!(matches!(
+            CMYK::from_colon_percent("0:0:0"),
+            Err(ColorParseError::WrongComponentCount { expected: 4, got: 3 })
+        ));
+        assert!(matches!(
+            CMYK::from_colon_percent("0:0:0:0:0"),
+            Err(ColorParseError::WrongComponentCount { expected: 4, got: 5 })
+        ));
+        assert!(matches!(CMYK::from_colon_percent("0:0:x:0"), Err(ColorParseError::ParseFailure(_))));
+    }
+
+    #[test]
+    fn slice_conversions() {
+        let pixels: Vec<RGB> = (0..600u32)
+            .map(|i| RGB::new((i / 3) as u8, (i / 7) as u8, (i * 13 / 5) as u8))
+            .collect();
+        let cmyk = CMYK::from_rgb_slice(&pixels);
+        let expected: Vec<CMYK> = pixels.iter().map(|&p| CMYK::from_rgb(p)).collect();
+        assert_eq!(cmyk, expected);
+        let rgb = CMYK::to_rgb_slice(&cmyk);
+        let expected: Vec<RGB> = cmyk.iter().map(|c| c.as_rgb()).collect();
+        assert_eq!(rgb, expected);
+        assert!(CMYK::from_rgb_slice(&[]).is_empty());
+    }
+
+    #[test]
+    fn ink_ordering() {
+        let light = CMYK::new(0.1, 0.0, 0.0, 0.0);
+        let medium = CMYK::new(0.0, 0.5, 0.5, 0.0);
+        let heavy = CMYK::new(0.2, 0.2, 0.2, 0.9);
+        let mut colors = [heavy, light, medium];
+        sort_by_ink(&mut colors);
+        assert_eq!(colors, [light, medium, heavy]);
+        assert_eq!(light.cmp_by_ink(&heavy), Ordering::Less);
+        assert_eq!(CMYK::new(0.5, 0.0, 0.0, 0.0).cmp_by_ink(&CMYK::new(0.0, 0.0, 0.0, 0.5)), Ordering::Equal);
+        assert_eq!(CMYK::WHITE.total_ink(), 0);
+    }
+
+    #[test]
+    fn near_black_conversion() {
+        let near = RGB::new(1, 0, 0);
+        let exact = CMYK::from_rgb(near);
+        assert_eq!(exact.c, 0);
+        assert_eq!(exact.m, u16::MAX);
+        assert_eq!(exact.y, u16::MAX);
+        assert_eq!(CMYK::from_rgb_near_black(near, 2), CMYK::BLACK);
+        assert_eq!(CMYK::from_rgb_near_black(near, 0), exact);
+        let dark = RGB::new(0x20, 0x10, 0x10);
+        assert_eq!(CMYK::from_rgb_near_black(dark, 2), CMYK::from_rgb(dark));
+    }
+
+    #[test]
+    fn as_rgb_boundaries() {
+        assert_eq!(CMYK::from_parts([0, 0, 0, 0]).as_rgb(), RGB::WHITE);
+        assert_eq!(CMYK::from_parts([u16::MAX; 4]).as_rgb(), RGB::BLACK);
+        assert_eq!(CMYK::from_parts([1, 0, u16::MAX - 1, 1]).as_rgb(), RGB::new(0xFF, 0xFF, 0));
+        assert_eq!(CMYK::from_parts([0, u16::MAX, 0, 0]).as_rgb(), RGB::new(0xFF, 0, 0xFF));
+    }
+}
@@ -0,0 +1,93 @@
+//! Colormaps that map a value in `[0.0, 1.0]` to a color, for data visualization
+
+use crate::hue::hsl_to_rgb;
+use crate::rgb::RGB;
+
+impl RGB {
+    /// Maps `t` onto a rainbow that sweeps the HSL hue from red at `0.0` through yellow, green,
+    /// cyan and blue to violet at `1.0`, at full saturation. `t` is clamped to `[0.0, 1.0]`.
+    /// Rainbows are easy to read but not perceptually uniform, so they can suggest edges in
+    /// smooth data.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::rainbow(0.0), RGB::new(0xFF, 0, 0));
+    /// assert_eq!(RGB::rainbow(1.0), RGB::new(0xFF, 0, 0xFF));
+    /// ```
+    pub fn rainbow(t: f64) -> RGB {
+        hsl_to_rgb([300.0 * t.clamp(0.0, 1.0), 1.0, 0.5])
+    }
+
+    /// Maps `t` onto matplotlib's perceptually uniform viridis colormap, from dark purple at
+    /// `0.0` through teal to yellow at `1.0`. `t` is clamped to `[0.0, 1.0]`.
+    /// Uses Matt Zucker's degree-6 polynomial fit, which is within a few units of the
+    /// published table.
+    /// # Examples
+    /// ```
+    /// use color_changer::Color;
+    /// use color_changer::rgb::RGB;
+    /// assert!(RGB::viridis(0.0).eq_rgb_within(&RGB::new(0x44, 0x01, 0x54), 4));
+    /// ```
+    pub fn viridis(t: f64) -> RGB {
+        polynomial_map(&VIRIDIS, t)
+    }
+}
+
+/// Polynomial coefficients for each of red, green and blue, lowest degree first
+const VIRIDIS: [[f64; 7]; 3] = [
+    [0.2777273272234177, 0.1050930431085774, -0.3308618287255563, -4.634230498983486,
+        6.228269936347081, 4.776384997670288, -5.435455855934631],
+    [0.005407344544966578, 1.404613529898575, 0.214847559468213, -5.799100973351585,
+        14.17993336680509, -13.74514537774601, 4.645852612178535],
+    [0.3340998053353061, 1.384590162594685, 0.09509516302823659, -19.33244095627987,
+        56.69055260068105, -65.35303263337234, 26.3124352495832],
+];
+
+fn polynomial_map(coefficients: &[[f64; 7]; 3], t: f64) -> RGB {
+    let t = t.clamp(0.0, 1.0);
+    let [r, g, b] = coefficients.map(|c| c.iter().rev().fold(0.0, |acc, &k| acc * t + k));
+    let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    RGB::new(channel(r), channel(g), channel(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    fn max_step(f: fn(f64) -> RGB) -> u8 {
+        let samples: Vec<RGB> = (0..=1000).map(|i| f(i as f64 / 1000.0)).collect();
+        samples.windows(2)
+            .map(|w| (0..3).map(|i| w[0].as_parts()[i].abs_diff(w[1].as_parts()[i])).max().unwrap())
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn viridis() {
+        let reference = [
+            (0.0, RGB::new(0x44, 0x01, 0x54)),
+            (0.25, RGB::new(0x3B, 0x52, 0x8B)),
+            (0.5, RGB::new(0x21, 0x91, 0x8C)),
+            (0.75, RGB::new(0x5E, 0xC9, 0x62)),
+            (1.0, RGB::new(0xFD, 0xE7, 0x25)),
+        ];
+        for &(t, expected) in &reference {
+            let got = RGB::viridis(t);
+            assert!(got.eq_rgb_within(&expected, 4), "{}: {} vs {}", t, got, expected);
+        }
+        assert!(max_step(RGB::viridis) <= 3);
+    }
+
+    #[test]
+    fn rainbow() {
+        let red = RGB::rainbow(0.0);
+        assert!(red.r == 0xFF && red.g == 0 && red.b == 0);
+        let violet = RGB::rainbow(1.0);
+        assert!(violet.b == 0xFF && violet.g == 0);
+        assert_eq!(RGB::rainbow(0.4), RGB::new(0, 0xFF, 0));
+        assert_eq!(RGB::rainbow(-1.0), red);
+        assert_eq!(RGB::rainbow(2.0), violet);
+        assert!(max_step(RGB::rainbow) <= 8);
+    }
+}
@@ -0,0 +1,154 @@
+//! Luminance and contrast calculations following the WCAG 2 definitions
+
+use crate::cie;
+use crate::rgb::RGB;
+
+/// The WCAG 2 conformance levels for text contrast, ordered from worst to best
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum WcagLevel {
+    /// Meets neither AA nor AAA
+    Fail,
+    /// At least 4.5:1, or 3:1 for large text
+    AA,
+    /// At least 7:1, or 4.5:1 for large text
+    AAA,
+}
+
+impl RGB {
+    /// Returns the WCAG relative luminance of this color, from `0.0` for black to `1.0` for white
+    pub fn relative_luminance(&self) -> f64 {
+        let [r, g, b] = self.as_linear();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// Returns whether this color is light, i.e. its relative luminance is above `0.5`.
+    /// See [RGB::is_light_threshold] to use a different cutoff.
+    pub fn is_light(&self) -> bool {
+        self.is_light_threshold(0.5)
+    }
+
+    /// Returns whether this color's relative luminance is above the threshold `t`, in `[0.0, 1.0]`.
+    /// A threshold near `0.18` instead splits colors by whether black or white text has more contrast.
+    pub fn is_light_threshold(&self, t: f64) -> bool {
+        self.relative_luminance() > t
+    }
+
+    /// Returns the WCAG contrast ratio between this color and `other`, from `1.0` (no contrast)
+    /// to `21.0` (black on white). The ratio is symmetric.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert!((RGB::BLACK.contrast_ratio(&RGB::WHITE) - 21.0).abs() < 1e-9);
+    /// ```
+    pub fn contrast_ratio(&self, other: &RGB) -> f64 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Returns the WCAG level that this color meets as text on `background`.
+    /// Large text (at least 18pt, or 14pt bold) has lower thresholds.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// use color_changer::contrast::WcagLevel;
+    /// assert_eq!(RGB::BLACK.wcag_level(&RGB::WHITE, false), WcagLevel::AAA);
+    /// ```
+    pub fn wcag_level(&self, background: &RGB, large_text: bool) -> WcagLevel {
+        let ratio = self.contrast_ratio(background);
+        let (aa, aaa) = if large_text { (3.0, 4.5) } else { (4.5, 7.0) };
+        if ratio >= aaa {
+            WcagLevel::AAA
+        } else if ratio >= aa {
+            WcagLevel::AA
+        } else {
+            WcagLevel::Fail
+        }
+    }
+
+    /// Lightens or darkens this color, keeping its L*a*b* hue and chroma, until its contrast
+    /// ratio against `background` is at least `target_ratio`.
+    /// Of the two directions, the one needing the smaller change in lightness is used.
+    /// If the target can't be met, whichever of black and white has the most contrast is returned.
+    pub fn adjust_for_contrast(&self, background: &RGB, target_ratio: f64) -> RGB {
+        if self.contrast_ratio(background) >= target_ratio {
+            return *self;
+        }
+        let [l, a, b] = cie::rgb_to_lab(*self);
+        const STEP: f64 = 0.25;
+        let steps = (100.0 / STEP) as usize;
+        for i in 1..=steps {
+            let delta = i as f64 * STEP;
+            let candidates = [(l + delta).min(100.0), (l - delta).max(0.0)];
+            for &l in &candidates {
+                let c = cie::lab_to_rgb([l, a, b]);
+                if c.contrast_ratio(background) >= target_ratio {
+                    return c;
+                }
+            }
+        }
+        if RGB::WHITE.contrast_ratio(background) >= RGB::BLACK.contrast_ratio(background) {
+            RGB::WHITE
+        } else {
+            RGB::BLACK
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contrast_ratio() {
+        assert!((RGB::WHITE.relative_luminance() - 1.0).abs() < 1e-9);
+        assert_eq!(RGB::BLACK.relative_luminance(), 0.0);
+        let grey = RGB::new(0x77, 0x77, 0x77);
+        assert!((grey.contrast_ratio(&RGB::WHITE) - 4.48).abs() < 0.01);
+        assert_eq!(grey.contrast_ratio(&RGB::WHITE), RGB::WHITE.contrast_ratio(&grey));
+    }
+
+    #[test]
+    fn is_light() {
+        assert!(RGB::WHITE.is_light());
+        assert!(!RGB::BLACK.is_light());
+        assert!(!RGB::BLACK.is_light_threshold(0.0));
+        let grey = RGB::new(0x80, 0x80, 0x80);
+        assert!(!grey.is_light());
+        assert!(grey.is_light_threshold(0.2));
+        assert!(!grey.is_light_threshold(0.25));
+    }
+
+    #[test]
+    fn wcag_level() {
+        let white = RGB::WHITE;
+        // About 5.7:1
+        let grey = RGB::new(0x6E, 0x6E, 0x6E);
+        assert_eq!(grey.wcag_level(&white, false), WcagLevel::AA);
+        assert_eq!(grey.wcag_level(&white, true), WcagLevel::AAA);
+        // About 2.3:1
+        let light = RGB::new(0xA0, 0xA0, 0xA0);
+        assert_eq!(light.wcag_level(&white, false), WcagLevel::Fail);
+        assert_eq!(light.wcag_level(&white, true), WcagLevel::Fail);
+        assert!(WcagLevel::Fail < WcagLevel::AA && WcagLevel::AA < WcagLevel::AAA);
+    }
+
+    #[test]
+    fn adjust_for_contrast() {
+        let background = RGB::new(0xF0, 0xF0, 0xF0);
+        let fg = RGB::new(0xA0, 0xA0, 0xFF);
+        assert!(fg.contrast_ratio(&background) < 4.5);
+        let adjusted = fg.adjust_for_contrast(&background, 4.5);
+        assert!(adjusted.contrast_ratio(&background) >= 4.5);
+        assert!(adjusted.relative_luminance() < fg.relative_luminance());
+        assert!(adjusted.b > adjusted.r);
+
+        let dark_bg = RGB::new(0x10, 0x10, 0x10);
+        let lightened = RGB::new(0x30, 0x30, 0x30).adjust_for_contrast(&dark_bg, 4.5);
+        assert!(lightened.contrast_ratio(&dark_bg) >= 4.5);
+
+        assert_eq!(fg.adjust_for_contrast(&background, 1.0), fg);
+        let mid = RGB::new(0x60, 0x60, 0x60);
+        assert_eq!(RGB::new(0x70, 0x70, 0x70).adjust_for_contrast(&mid, 21.0), RGB::WHITE);
+    }
+}
@@ -0,0 +1,103 @@
+//! Simulation of color vision deficiencies (color blindness), for previewing how a color appears
+//! to viewers with dichromatic vision.
+
+use crate::rgb::RGB;
+
+/// The kinds of dichromatic color vision deficiency that can be simulated
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Cvd {
+    /// Missing long-wavelength (red) cones
+    Protanopia,
+    /// Missing medium-wavelength (green) cones
+    Deuteranopia,
+    /// Missing short-wavelength (blue) cones
+    Tritanopia,
+}
+
+type Matrix = [[f64; 3]; 3];
+
+const RGB_TO_LMS: Matrix = [
+    [17.8824, 43.5161, 4.11935],
+    [3.45565, 27.1554, 3.86714],
+    [0.0299566, 0.184309, 1.46709],
+];
+
+const LMS_TO_RGB: Matrix = [
+    [0.0809444479, -0.130504409, 0.116721066],
+    [-0.0102485335, 0.0540193266, -0.113614708],
+    [-0.000365296938, -0.00412161469, 0.693511405],
+];
+
+fn mul(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+    let row = |i: usize| m[i][0] * v[0] + m[i][1] * v[1] + m[i][2] * v[2];
+    [row(0), row(1), row(2)]
+}
+
+impl Cvd {
+    /// The LMS-space projection that collapses the missing cone response onto the remaining two
+    fn lms_projection(self) -> Matrix {
+        match self {
+            Cvd::Protanopia => [
+                [0.0, 2.02344, -2.52581],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+            ],
+            Cvd::Deuteranopia => [
+                [1.0, 0.0, 0.0],
+                [0.494207, 0.0, 1.24827],
+                [0.0, 0.0, 1.0],
+            ],
+            Cvd::Tritanopia => [
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [-0.395913, 0.801109, 0.0],
+            ],
+        }
+    }
+}
+
+impl RGB {
+    /// Simulates how this color appears under the given color vision deficiency.
+    ///
+    /// The color is converted to linear RGB, then to LMS cone space, where the missing cone
+    /// response is reconstructed from the other two, and finally converted back to sRGB.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// use color_changer::cvd::Cvd;
+    /// let grey = RGB::new(128, 128, 128);
+    /// assert_eq!(grey.simulate_cvd(Cvd::Deuteranopia), grey);
+    /// ```
+    pub fn simulate_cvd(&self, kind: Cvd) -> RGB {
+        let lms = mul(&RGB_TO_LMS, self.as_linear());
+        let lms = mul(&kind.lms_projection(), lms);
+        RGB::from_linear(mul(&LMS_TO_RGB, lms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protanopia_red_shifts_toward_olive() {
+        let red = RGB::new(0xFF, 0, 0);
+        let sim = red.simulate_cvd(Cvd::Protanopia);
+        assert!(sim.g > 0x40, "{:?}", sim);
+        assert!(sim.r.abs_diff(sim.g) < 0x30, "{:?}", sim);
+        assert!(sim.b < sim.g, "{:?}", sim);
+    }
+
+    #[test]
+    fn greys_are_unchanged() {
+        for &v in &[0u8, 64, 128, 200, 255] {
+            let grey = RGB::new(v, v, v);
+            for &kind in &[Cvd::Protanopia, Cvd::Deuteranopia, Cvd::Tritanopia] {
+                let sim = grey.simulate_cvd(kind);
+                for (a, b) in sim.as_parts().iter().zip(grey.as_parts().iter()) {
+                    assert!(a.abs_diff(*b) <= 1, "{:?} {:?} {:?}", kind, grey, sim);
+                }
+            }
+        }
+    }
+}
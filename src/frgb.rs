@@ -0,0 +1,128 @@
+//! Represents RGB colors with `f64` components in `[0.0, 1.0]`.
+//! Converting between other color types through [FRGB] rather than [RGB] avoids accumulating
+//! `u8` rounding error in chained conversions.
+
+use crate::cmyk::CMYK;
+use crate::rgb::RGB;
+use crate::Color;
+use std::fmt::Display;
+
+/// An RGB color with `f64` components, where each component is nominally in `[0.0, 1.0]`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FRGB {
+    /// The red component
+    pub r: f64,
+    /// The green component
+    pub g: f64,
+    /// The blue component
+    pub b: f64,
+}
+
+impl Color for FRGB {
+    fn as_rgb(&self) -> RGB {
+        self.to_rgb8()
+    }
+
+    fn as_rgb_f64(&self) -> [f64; 3] {
+        self.as_parts().map(|c| c * 255.0)
+    }
+
+    fn from_rgb(c: RGB) -> Self {
+        FRGB::from_rgb8(c)
+    }
+}
+
+impl Display for FRGB {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({:.4},{:.4},{:.4})", self.r, self.g, self.b)
+    }
+}
+
+impl FRGB {
+    /// Creates a color from raw float components
+    pub const fn new(r: f64, g: f64, b: f64) -> Self {
+        FRGB { r, g, b }
+    }
+
+    /// Converts an RGB-255 color into floats, exactly
+    pub fn from_rgb8(c: RGB) -> Self {
+        FRGB::new(c.r as f64 / 255.0, c.g as f64 / 255.0, c.b as f64 / 255.0)
+    }
+
+    /// Converts into RGB-255, clamping each component into `[0.0, 1.0]` and rounding
+    pub fn to_rgb8(&self) -> RGB {
+        let conv = |f: f64| (f.clamp(0.0, 1.0) * 255.0).round() as u8;
+        RGB::new(conv(self.r), conv(self.g), conv(self.b))
+    }
+
+    /// Returns the components as an array, in order RGB
+    pub fn as_parts(&self) -> [f64; 3] {
+        [self.r, self.g, self.b]
+    }
+}
+
+impl From<CMYK> for FRGB {
+    fn from(c: CMYK) -> Self {
+        let [c, m, y, k] = c.as_parts();
+        FRGB::new((1.0 - c) * (1.0 - k), (1.0 - m) * (1.0 - k), (1.0 - y) * (1.0 - k))
+    }
+}
+
+impl From<FRGB> for CMYK {
+    fn from(c: FRGB) -> Self {
+        // Non-finite components would survive the clamp as NaN, so treat them as 0.0
+        let [r, g, b] = c.as_parts().map(|f| if f.is_finite() { f.clamp(0.0, 1.0) } else { 0.0 });
+        let k = 1.0 - r.max(g).max(b);
+        if k >= 1.0 {
+            return CMYK::BLACK;
+        }
+        let conv = |f: f64| ((1.0 - f - k) / (1.0 - k)).clamp(0.0, 1.0);
+        CMYK::new(conv(r), conv(g), conv(b), k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgb8_roundtrip() {
+        for v in 0..=255u8 {
+            let c = RGB::new(v, 255 - v, v / 2);
+            assert_eq!(FRGB::from_rgb8(c).to_rgb8(), c);
+        }
+        assert_eq!(FRGB::new(1.5, -0.2, 0.5).to_rgb8(), RGB::new(255, 0, 128));
+    }
+
+    #[test]
+    fn as_rgb_f64() {
+        assert_eq!(FRGB::new(0.5, 1.0, 0.0).as_rgb_f64(), [127.5, 255.0, 0.0]);
+    }
+
+    #[test]
+    fn non_finite_into_cmyk() {
+        let partial = CMYK::from(FRGB::new(f64::NAN, 0.5, 0.5));
+        assert_eq!(partial, CMYK::from(FRGB::new(0.0, 0.5, 0.5)));
+        assert_eq!(CMYK::from(FRGB::new(f64::NAN, f64::NAN, f64::NAN)), CMYK::BLACK);
+        assert_eq!(CMYK::from(FRGB::new(f64::INFINITY, 0.0, 0.0)), CMYK::BLACK);
+    }
+
+    #[test]
+    fn drifts_less_than_rgb() {
+        let start = CMYK::new(0.13, 0.61, 0.0, 0.29);
+        let mut via_rgb = start;
+        let mut via_frgb = start;
+        for _ in 0..10 {
+            via_rgb = CMYK::from_rgb(via_rgb.as_rgb());
+            via_frgb = CMYK::from(FRGB::from(via_frgb));
+        }
+        let drift = |c: CMYK| {
+            c.as_parts().iter()
+                .zip(start.as_parts().iter())
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f64::max)
+        };
+        assert!(drift(via_frgb) < 1e-4, "{}", drift(via_frgb));
+        assert!(drift(via_frgb) < drift(via_rgb));
+    }
+}
@@ -0,0 +1,184 @@
+//! Gradients between colors, defined by positioned color stops
+
+use crate::rgb::RGB;
+use thiserror::Error as ThisErr;
+
+/// Represents the reasons a set of gradient stops may be invalid
+#[derive(ThisErr, Clone, Debug)]
+pub enum GradientError {
+    /// Occurs when a gradient is built without any stops
+    #[error("A gradient needs at least one stop")]
+    NoStops,
+    /// Occurs when a stop's position is outside `[0.0, 1.0]`
+    #[error("Stop position {0} is outside [0.0, 1.0]")]
+    OutOfRange(f64),
+    /// Occurs when a stop is positioned before the stop preceding it
+    #[error("Stop {index} is positioned before the previous stop")]
+    Unsorted {
+        /// The index of the offending stop
+        index: usize
+    },
+}
+
+/// Builds a gradient declaratively from stops at positions in `[0.0, 1.0]`, which must be added
+/// in non-decreasing order.
+/// # Examples
+/// ```
+/// use color_changer::rgb::RGB;
+/// use color_changer::gradient::GradientBuilder;
+/// let colors = GradientBuilder::new()
+///     .stop(0.0, RGB::BLACK)
+///     .stop(1.0, RGB::WHITE)
+///     .build(3)
+///     .unwrap();
+/// assert_eq!(colors, vec![RGB::BLACK, RGB::new(0x80, 0x80, 0x80), RGB::WHITE]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GradientBuilder {
+    stops: Vec<(f64, RGB)>,
+}
+
+impl GradientBuilder {
+    /// Creates a builder with no stops
+    pub fn new() -> Self {
+        GradientBuilder::default()
+    }
+
+    /// Adds a stop with color `c` at position `t`
+    pub fn stop(mut self, t: f64, c: RGB) -> Self {
+        self.stops.push((t, c));
+        self
+    }
+
+    /// Validates the stops and samples `steps` evenly spaced colors from the first position
+    /// (`0.0`) to the last (`1.0`), inclusive. Positions before the first stop or after the last
+    /// take that stop's color.
+    pub fn build(&self, steps: usize) -> Result<Vec<RGB>, GradientError> {
+        validate_stops(&self.stops)?;
+        let colors = (0..steps)
+            .map(|i| if steps == 1 { 0.0 } else { i as f64 / (steps - 1) as f64 })
+            .map(|t| sample_stops(&self.stops, t))
+            .collect();
+        Ok(colors)
+    }
+}
+
+/// A gradient that can be sampled at any position, made of stops sorted by position
+/// # Examples
+/// ```
+/// use color_changer::rgb::RGB;
+/// use color_changer::gradient::Gradient;
+/// let g = Gradient::new(vec![(1.0, RGB::WHITE), (0.0, RGB::BLACK)]).unwrap();
+/// assert_eq!(g.sample(0.5), RGB::new(0x80, 0x80, 0x80));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    stops: Vec<(f64, RGB)>,
+}
+
+impl Gradient {
+    /// Creates a gradient from stops at positions in `[0.0, 1.0]`, sorting them by position.
+    /// Stops at the same position keep their relative order, giving a hard edge.
+    pub fn new(mut stops: Vec<(f64, RGB)>) -> Result<Self, GradientError> {
+        if let Some(&(t, _)) = stops.iter().find(|(t, _)| t.is_nan()) {
+            return Err(GradientError::OutOfRange(t));
+        }
+        stops.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+        validate_stops(&stops)?;
+        Ok(Gradient { stops })
+    }
+
+    /// Returns the stops, sorted by position
+    pub fn stops(&self) -> &[(f64, RGB)] {
+        &self.stops
+    }
+
+    /// Samples the gradient at `t` by linearly interpolating in RGB between the surrounding stops.
+    /// Positions before the first stop or after the last, including any outside `[0.0, 1.0]`,
+    /// take that end stop's color.
+    pub fn sample(&self, t: f64) -> RGB {
+        sample_stops(&self.stops, t.clamp(0.0, 1.0))
+    }
+}
+
+fn validate_stops(stops: &[(f64, RGB)]) -> Result<(), GradientError> {
+    if stops.is_empty() {
+        return Err(GradientError::NoStops);
+    }
+    if let Some(&(t, _)) = stops.iter().find(|(t, _)| !(0.0..=1.0).contains(t)) {
+        return Err(GradientError::OutOfRange(t));
+    }
+    if let Some(i) = stops.windows(2).position(|w| w[1].0 < w[0].0) {
+        return Err(GradientError::Unsorted { index: i + 1 });
+    }
+    Ok(())
+}
+
+/// Piecewise-linearly interpolates the sorted, non-empty `stops` at `t`
+fn sample_stops(stops: &[(f64, RGB)], t: f64) -> RGB {
+    let (first_t, first) = stops[0];
+    if t <= first_t {
+        return first;
+    }
+    for w in stops.windows(2) {
+        let (t0, c0) = w[0];
+        let (t1, c1) = w[1];
+        if t <= t1 {
+            return lerp(c0, c1, (t - t0) / (t1 - t0));
+        }
+    }
+    stops[stops.len() - 1].1
+}
+
+fn lerp(a: RGB, b: RGB, t: f64) -> RGB {
+    let mix = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * t).round() as u8;
+    RGB::new(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: RGB = RGB::new(0xFF, 0, 0);
+    const BLUE: RGB = RGB::new(0, 0, 0xFF);
+
+    #[test]
+    fn two_stop_build() {
+        let colors = GradientBuilder::new().stop(0.0, RED).stop(1.0, BLUE).build(5).unwrap();
+        assert_eq!(colors.len(), 5);
+        assert_eq!(colors[0], RED);
+        assert_eq!(colors[2], RGB::new(0x80, 0, 0x80));
+        assert_eq!(colors[4], BLUE);
+
+        let held = GradientBuilder::new().stop(0.5, RED).build(3).unwrap();
+        assert_eq!(held, vec![RED; 3]);
+    }
+
+    #[test]
+    fn sample() {
+        let g = Gradient::new(vec![(0.0, RED), (1.0, BLUE)]).unwrap();
+        assert_eq!(g.sample(0.0), RED);
+        assert_eq!(g.sample(0.5), RGB::new(0x80, 0, 0x80));
+        assert_eq!(g.sample(1.0), BLUE);
+        assert_eq!(g.sample(-3.0), RED);
+        assert_eq!(g.sample(7.5), BLUE);
+
+        let g = Gradient::new(vec![(0.75, BLUE), (0.25, RED), (0.5, RGB::WHITE)]).unwrap();
+        assert_eq!(g.stops()[0], (0.25, RED));
+        assert_eq!(g.sample(0.1), RED);
+        assert_eq!(g.sample(0.5), RGB::WHITE);
+        assert_eq!(g.sample(0.9), BLUE);
+
+        assert!(matches!(Gradient::new(vec![]), Err(GradientError::NoStops)));
+        assert!(matches!(Gradient::new(vec![(f64::NAN, RED)]), Err(GradientError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn invalid_stops() {
+        let unsorted = GradientBuilder::new().stop(0.0, RED).stop(1.0, BLUE).stop(0.5, RED).build(4);
+        assert!(matches!(unsorted, Err(GradientError::Unsorted { index: 2 })));
+        let out_of_range = GradientBuilder::new().stop(1.5, RED).build(4);
+        assert!(matches!(out_of_range, Err(GradientError::OutOfRange(_))));
+        assert!(matches!(GradientBuilder::new().build(4), Err(GradientError::NoStops)));
+    }
+}
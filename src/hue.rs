@@ -0,0 +1,281 @@
+//! Utilities for working with hue angles, in degrees
+
+use crate::rgb::RGB;
+
+/// The hue ranges, in degrees, that [RGB::is_warm] treats as warm: reds through yellows,
+/// and magentas back round to red
+pub const WARM_HUE_RANGES: [(f64, f64); 2] = [(0.0, 60.0), (300.0, 360.0)];
+
+impl RGB {
+    /// Returns whether this color's hue is warm, i.e. within one of the [WARM_HUE_RANGES].
+    /// Greys have no hue and are neither warm nor cool, so they return `false`.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert!(RGB::new(0xFF, 0x80, 0).is_warm());
+    /// assert!(!RGB::new(0, 0x80, 0xFF).is_warm());
+    /// ```
+    pub fn is_warm(&self) -> bool {
+        self.is_warm_with_ranges(&WARM_HUE_RANGES)
+    }
+
+    /// Returns an infinite iterator of colors starting at `start`, with the HSL hue advancing by
+    /// `step_degrees` for each color and wrapping at 360°. Saturation and lightness are kept, so
+    /// a grey `start` repeats forever.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// let mut cycle = RGB::cycle_hue(RGB::new(0xFF, 0, 0), 120.0);
+    /// assert_eq!(cycle.nth(1), Some(RGB::new(0, 0xFF, 0)));
+    /// ```
+    pub fn cycle_hue(start: RGB, step_degrees: f64) -> impl Iterator<Item = RGB> {
+        let [h, s, l] = rgb_to_hsl(start);
+        (0u64..).map(move |i| hsl_to_rgb([normalize_hue(h + i as f64 * step_degrees), s, l]))
+    }
+
+    /// Returns whether this color's hue falls within any of the `(start, end)` ranges in degrees.
+    /// Each range includes `start` and excludes `end`; a range with `start > end` wraps through
+    /// 0°, so `(300.0, 60.0)` covers magentas through yellows. Greys always return `false`.
+    pub fn is_warm_with_ranges(&self, ranges: &[(f64, f64)]) -> bool {
+        let [h, s, _] = rgb_to_hsl(*self);
+        if s == 0.0 {
+            return false;
+        }
+        ranges.iter().any(|&(start, end)| {
+            if start <= end {
+                start <= h && h < end
+            } else {
+                start <= h || h < end
+            }
+        })
+    }
+}
+
+/// Wraps a hue in degrees into `[0.0, 360.0)`, so `-30` becomes `330` and `720` becomes `0`.
+/// All hue arithmetic in this crate goes through it.
+/// # Examples
+/// ```
+/// use color_changer::hue::normalize_hue;
+/// assert_eq!(normalize_hue(-30.0), 330.0);
+/// assert_eq!(normalize_hue(370.0), 10.0);
+/// ```
+pub fn normalize_hue(h: f64) -> f64 {
+    let h = h.rem_euclid(360.0);
+    // rem_euclid rounds tiny negative angles up to exactly 360.0
+    if h >= 360.0 { 0.0 } else { h }
+}
+
+/// Which way round the hue wheel [lerp_hue] travels, as in CSS hue interpolation
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum HueDirection {
+    /// Take the shorter arc, at most 180°
+    Shorter,
+    /// Take the longer arc, at least 180°
+    Longer,
+    /// Always move towards larger angles, wrapping from 360° to 0°
+    Increasing,
+    /// Always move towards smaller angles, wrapping from 0° to 360°
+    Decreasing,
+}
+
+/// Interpolates from hue `from` to hue `to`, both in degrees, travelling in `direction`.
+/// `t` is not clamped; the result is in `[0.0, 360.0)`. Equal hues stay put in every direction.
+/// # Examples
+/// ```
+/// use color_changer::hue::{lerp_hue, HueDirection};
+/// assert!(lerp_hue(10.0, 350.0, 0.5, HueDirection::Shorter).abs() < 1e-9);
+/// assert!((lerp_hue(10.0, 350.0, 0.5, HueDirection::Longer) - 180.0).abs() < 1e-9);
+/// ```
+pub fn lerp_hue(from: f64, to: f64, t: f64, direction: HueDirection) -> f64 {
+    let d = normalize_hue(to - from);
+    let delta = match direction {
+        HueDirection::Shorter if d > 180.0 => d - 360.0,
+        HueDirection::Longer if d > 0.0 && d < 180.0 => d - 360.0,
+        HueDirection::Decreasing if d > 0.0 => d - 360.0,
+        _ => d,
+    };
+    normalize_hue(from + t * delta)
+}
+
+/// Returns the circular mean of two hues in degrees, in `[0.0, 360.0)`.
+/// Hues are averaged as unit vectors, so `350` and `10` average to `0` rather than `180`.
+/// The mean of opposite hues, such as `0` and `180`, is undefined and returns `NaN`.
+/// # Examples
+/// ```
+/// use color_changer::hue::circular_hue_mean;
+/// assert!((circular_hue_mean(80.0, 100.0) - 90.0).abs() < 1e-9);
+/// assert!(circular_hue_mean(0.0, 180.0).is_nan());
+/// ```
+pub fn circular_hue_mean(a: f64, b: f64) -> f64 {
+    circular_hue_mean_of(&[a, b]).unwrap_or(f64::NAN)
+}
+
+/// Returns the circular mean of any number of hues in degrees, in `[0.0, 360.0)`.
+/// Returns `None` if `hues` is empty or the hues cancel out, e.g. three hues 120° apart,
+/// since the mean is undefined.
+pub fn circular_hue_mean_of(hues: &[f64]) -> Option<f64> {
+    let (x, y) = hues.iter()
+        .map(|h| h.to_radians())
+        .fold((0.0, 0.0), |(x, y), h| (x + h.cos(), y + h.sin()));
+    if hues.is_empty() || x.hypot(y) < 1e-9 * hues.len() as f64 {
+        return None;
+    }
+    Some(normalize_hue(y.atan2(x).to_degrees()))
+}
+
+/// Converts an RGB-255 color into `[hue, saturation, lightness]`, with the hue in degrees
+/// in `[0.0, 360.0)` and the others in `[0.0, 1.0]`. Greys have a hue of `0.0`.
+pub(crate) fn rgb_to_hsl(c: RGB) -> [f64; 3] {
+    let [r, g, b] = c.as_parts().map(|v| v as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return [0.0, 0.0, l];
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        (g - b) / d
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    [normalize_hue(h * 60.0), s, l]
+}
+
+/// Converts an RGB-255 color into `[hue, saturation, value]`, with the hue in degrees in
+/// `[0.0, 360.0)`, the same as [rgb_to_hsl], and the others in `[0.0, 1.0]`
+pub(crate) fn rgb_to_hsv(c: RGB) -> [f64; 3] {
+    let [r, g, b] = c.as_parts().map(|v| v as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+    [rgb_to_hsl(c)[0], s, max]
+}
+
+/// Converts `[hue, saturation, lightness]` as produced by [rgb_to_hsl] back into RGB-255.
+/// The hue may be any angle; saturation and lightness are clamped to `[0.0, 1.0]`.
+pub(crate) fn hsl_to_rgb(hsl: [f64; 3]) -> RGB {
+    let [h, s, l] = hsl;
+    let h = normalize_hue(h) / 60.0;
+    let s = s.clamp(0.0, 1.0);
+    let l = l.clamp(0.0, 1.0);
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match h as u8 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let conv = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    RGB::new(conv(r), conv(g), conv(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hue_diff(a: f64, b: f64) -> f64 {
+        let d = (a - b).rem_euclid(360.0);
+        d.min(360.0 - d)
+    }
+
+    #[test]
+    fn normalize_hue() {
+        assert_eq!(super::normalize_hue(-30.0), 330.0);
+        assert_eq!(super::normalize_hue(370.0), 10.0);
+        assert_eq!(super::normalize_hue(720.0), 0.0);
+        assert_eq!(super::normalize_hue(0.0), 0.0);
+        assert_eq!(super::normalize_hue(359.5), 359.5);
+        assert_eq!(super::normalize_hue(-1e-20), 0.0);
+        assert!((super::normalize_hue(-3610.0) - 350.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lerp_hue_directions() {
+        use HueDirection::*;
+        let path = |dir| -> Vec<f64> { (0..=4).map(|i| lerp_hue(10.0, 350.0, i as f64 / 4.0, dir)).collect() };
+        let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(&x, &y)| hue_diff(x, y) < 1e-9);
+
+        assert!(close(&path(Shorter), &[10.0, 5.0, 0.0, 355.0, 350.0]));
+        assert!(close(&path(Longer), &[10.0, 95.0, 180.0, 265.0, 350.0]));
+        assert!(close(&path(Increasing), &path(Longer)));
+        assert!(close(&path(Decreasing), &path(Shorter)));
+
+        assert!((lerp_hue(350.0, 10.0, 0.5, Increasing)).abs() < 1e-9);
+        assert!((lerp_hue(350.0, 10.0, 0.5, Decreasing) - 180.0).abs() < 1e-9);
+        for &dir in &[Shorter, Longer, Increasing, Decreasing] {
+            assert_eq!(lerp_hue(42.0, 42.0, 0.5, dir), 42.0);
+            assert!(hue_diff(lerp_hue(10.0, 350.0, 1.0, dir), 350.0) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn hsl_roundtrip() {
+        let [h, s, l] = rgb_to_hsl(RGB::new(0xFF, 0, 0));
+        assert_eq!((h, s, l), (0.0, 1.0, 0.5));
+        let [h, _, _] = rgb_to_hsl(RGB::new(0, 0, 0xFF));
+        assert!((h - 240.0).abs() < 1e-9);
+        for r in (0..=255u8).step_by(15) {
+            for g in (0..=255u8).step_by(17) {
+                let c = RGB::new(r, g, 255 - r / 2);
+                assert_eq!(hsl_to_rgb(rgb_to_hsl(c)), c);
+            }
+        }
+    }
+
+    #[test]
+    fn cycle_hue() {
+        let start = RGB::new(0xC0, 0x40, 0x60);
+        let colors: Vec<RGB> = RGB::cycle_hue(start, 15.0).take(360 / 15 + 1).collect();
+        assert_eq!(colors[0], start);
+        assert_eq!(colors[24], start);
+        assert_ne!(colors[12], start);
+        let [_, s, l] = rgb_to_hsl(start);
+        for c in &colors {
+            let [_, s2, l2] = rgb_to_hsl(*c);
+            assert!((s - s2).abs() < 0.02 && (l - l2).abs() < 0.01, "{:?}", c);
+        }
+        let far = RGB::cycle_hue(start, 7.0).nth(360).unwrap();
+        assert_eq!(far, start);
+    }
+
+    #[test]
+    fn warm_and_cool() {
+        assert!(RGB::new(0xFF, 0, 0).is_warm());
+        assert!(RGB::new(0xFF, 0, 0x20).is_warm());
+        assert!(RGB::new(0xFF, 0xCC, 0).is_warm());
+        assert!(!RGB::new(0, 0, 0xFF).is_warm());
+        assert!(!RGB::new(0, 0xFF, 0x80).is_warm());
+
+        let grey = RGB::new(0x80, 0x80, 0x80);
+        assert!(!grey.is_warm());
+        assert!(!grey.is_warm_with_ranges(&[(0.0, 360.0)]));
+
+        let blue = RGB::new(0, 0, 0xFF);
+        assert!(blue.is_warm_with_ranges(&[(200.0, 250.0)]));
+        assert!(blue.is_warm_with_ranges(&[(230.0, 10.0)]));
+        assert!(!RGB::new(0, 0xFF, 0).is_warm_with_ranges(&[(230.0, 10.0)]));
+    }
+
+    #[test]
+    fn circular_means() {
+        assert!(hue_diff(circular_hue_mean(350.0, 10.0), 0.0) < 1e-9);
+        assert!(hue_diff(circular_hue_mean(340.0, 0.0), 350.0) < 1e-9);
+        assert!(hue_diff(circular_hue_mean(-30.0, 390.0), 0.0) < 1e-9);
+        assert!(circular_hue_mean(0.0, 180.0).is_nan());
+        assert!(circular_hue_mean(90.0, 270.0).is_nan());
+
+        assert!(hue_diff(circular_hue_mean_of(&[350.0, 0.0, 10.0, 20.0]).unwrap(), 5.0) < 0.5);
+        assert_eq!(circular_hue_mean_of(&[]), None);
+        assert_eq!(circular_hue_mean_of(&[0.0, 120.0, 240.0]), None);
+        let single = circular_hue_mean_of(&[200.0]).unwrap();
+        assert!((single - 200.0).abs() < 1e-9);
+    }
+}
@@ -5,35 +5,243 @@
 //! color_changer is a crate designed for conversions between color representations, i.e. `RGB <-> CMYK`
 
 use std::fmt::{Display};
-use regex::Regex;
-use once_cell::sync::Lazy;
 use std::num::ParseIntError;
+use std::io::BufRead;
 use crate::rgb::RGB;
+use crate::cmyk::CMYK;
+use crate::alpha::Alpha;
 use thiserror::Error as ThisErr;
-use crate::ColorParseError::BadInput;
 
+pub mod alpha;
+pub mod ansi;
 pub mod rgb;
 pub mod cmyk;
+pub mod colormap;
+pub mod contrast;
+pub mod cvd;
+pub mod frgb;
+pub mod gradient;
+pub mod hue;
+pub mod pixels;
+pub mod rec2020;
+pub mod scheme;
+mod cie;
+mod macros;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
-static HEX_RE: Lazy<Regex> = Lazy::new(
-    || Regex::new(r#"#?([0-9a-fA-F]{2})([0-9a-fA-F]{2})([0-9a-fA-F]{2})"#).unwrap()
-);
+/// Serializes a value as its [Display] message, for errors that don't implement `Serialize`
+#[cfg(feature = "serde")]
+fn serialize_display<T: Display, S: serde::Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+/// Lists lengths for an error message, e.g. `"3, 6 or 8"`
+fn join_lengths(lengths: &[usize]) -> String {
+    match lengths.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => {
+            let rest: Vec<String> = rest.iter().map(|n| n.to_string()).collect();
+            format!("{} or {}", rest.join(", "), last)
+        }
+    }
+}
 
-/// Represents the potential reasons parsing a hex string into a color could fail
+/// Represents the reasons building a color from outside input could fail: malformed hex or
+/// component strings, values out of range or over an ink limit, mismatched pixel buffers,
+/// and failed reads. With the `serde` feature it serializes as structured data, with each variant's fields.
 #[derive(ThisErr, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ColorParseError {
     /// Occurs when the input does not match the hex color regex, like `"#FFABCD"` or `"ABFFED"`
     #[error("The input wasn't a valid hex color, e.g. #FFABCD or ABFFED")]
     BadInput,
-    /// Occurs when the input breaks u8's parse method.
-    #[error("A component of the hex string didn't parse: {0}")]
-    ParseFailure(#[from] ParseIntError)
+    /// Occurs when the input, after removing any leading `#`, has a number of hex digits the
+    /// format doesn't allow, e.g. 3, 6 or 8 for [Color::from_hex]
+    #[error("Expected {} hex digits after an optional '#', but got {got}", join_lengths(expected))]
+    WrongLength {
+        /// The number of characters after the optional `#`
+        got: usize,
+        /// The digit counts the format accepts
+        expected: &'static [usize]
+    },
+    /// Occurs when a color component, such as a hex pair or a `C:M:Y:K` percentage, isn't a
+    /// valid integer
+    #[error("A color component didn't parse: {0}")]
+    ParseFailure(
+        #[from]
+        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))]
+        ParseIntError
+    ),
+    /// Occurs when a delimited color, like CMYK's `C:M:Y:K`, has the wrong number of components
+    #[error("Expected {expected} components, but got {got}")]
+    WrongComponentCount {
+        /// The number of components the format requires
+        expected: usize,
+        /// The number of components in the input
+        got: usize
+    },
+    /// Occurs when a CSS color function like `rgb()` is malformed, with a description of the problem
+    #[error("Invalid CSS color: {0}")]
+    BadCss(String),
+    /// Occurs when a component parsed successfully but is outside its valid range
+    #[error("Component {component} is out of range: {value}")]
+    OutOfRange {
+        /// The name of the offending component, e.g. `'r'` or `'k'`
+        component: char,
+        /// The offending value
+        value: i64
+    },
+    /// Occurs when a CMYK color's total ink coverage, summed over its `[0.0, 1.0]` components,
+    /// is over the allowed limit
+    #[error("Total ink {total} exceeds the limit of {max}")]
+    InkLimitExceeded {
+        /// The total ink of the color
+        total: f64,
+        /// The allowed limit
+        max: f64
+    },
+    /// Occurs when two pixel buffers that must be the same length are not
+    #[error("Expected buffers of equal length, but got {left} and {right}")]
+    LengthMismatch {
+        /// The length of the first buffer
+        left: usize,
+        /// The length of the second buffer
+        right: usize
+    },
+    /// Occurs when reading the input failed, with the underlying error's message
+    #[error("Failed to read the input: {0}")]
+    ReadFailure(String),
+}
+
+/// Returned by [Color::try_into_color] when a conversion would change the color
+#[derive(ThisErr, Clone, Debug, Eq, PartialEq)]
+#[error("Conversion changed the color by up to {delta} in RGB-255")]
+pub struct LossyConversion {
+    /// The largest difference in any RGB-255 channel between the color before and after the
+    /// conversion
+    pub delta: u8,
+}
+
+/// Lazily parses one hex color per line from `r`, e.g. a `.hex` palette file.
+///
+/// Lines are trimmed of surrounding whitespace, then skipped if they are:
+/// - empty,
+/// - comments starting with `;`, or
+/// - comments starting with `#` where the `#` is not directly followed by a hex digit,
+///   such as `# Reds` or `#!`. A comment like `#add` should be written `# add` or `; add`.
+///
+/// Every other line is parsed with [Color::from_hex].
+/// # Examples
+/// ```
+/// use color_changer::parse_hex_reader;
+/// use color_changer::rgb::RGB;
+/// let file = "; my palette\n# Reds\n#FF0000\n\nAA0000\n";
+/// let colors: Vec<RGB> = parse_hex_reader(file.as_bytes()).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(colors, vec![RGB::new(0xFF, 0, 0), RGB::new(0xAA, 0, 0)]);
+/// ```
+pub fn parse_hex_reader<R: BufRead>(r: R) -> impl Iterator<Item = Result<RGB, ColorParseError>> {
+    r.lines().filter_map(|line| {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return Some(Err(ColorParseError::ReadFailure(e.to_string()))),
+        };
+        let line = line.trim();
+        let is_comment = line.starts_with(';')
+            || (line.starts_with('#') && !line[1..].starts_with(|c: char| c.is_ascii_hexdigit()));
+        if line.is_empty() || is_comment {
+            None
+        } else {
+            Some(RGB::from_hex(line))
+        }
+    })
+}
+
+/// The color spaces available for conversion at runtime, see [convert]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ColorSpace {
+    /// [RGB]
+    Rgb,
+    /// [CMYK]
+    Cmyk,
+    /// HSL, as `(hue,saturation,lightness)` with the hue in degrees
+    Hsl,
+    /// HSV, as `(hue,saturation,value)` with the hue in degrees
+    Hsv,
+    /// CIE L*a*b*, as `(L,a,b)` under the D65 white
+    Lab,
+}
+
+impl ColorSpace {
+    /// Every color space, for listing them at runtime
+    pub const ALL: [ColorSpace; 5] = [
+        ColorSpace::Rgb,
+        ColorSpace::Cmyk,
+        ColorSpace::Hsl,
+        ColorSpace::Hsv,
+        ColorSpace::Lab,
+    ];
+}
+
+/// Converts `rgb` into the color space chosen at runtime, returning that space's [Display] output
+/// # Examples
+/// ```
+/// use color_changer::{convert, ColorSpace};
+/// use color_changer::rgb::RGB;
+/// assert_eq!(convert(RGB::WHITE, ColorSpace::Cmyk), "(0.00,0.00,0.00,0.00)");
+/// ```
+pub fn convert(rgb: RGB, to: ColorSpace) -> String {
+    match to {
+        ColorSpace::Rgb => rgb.to_string(),
+        ColorSpace::Cmyk => CMYK::from_rgb(rgb).to_string(),
+        ColorSpace::Hsl => {
+            let [h, s, l] = hue::rgb_to_hsl(rgb);
+            format!("({:.1},{:.2},{:.2})", h, s, l)
+        }
+        ColorSpace::Hsv => {
+            let [h, s, v] = hue::rgb_to_hsv(rgb);
+            format!("({:.1},{:.2},{:.2})", h, s, v)
+        }
+        ColorSpace::Lab => {
+            // Greys come out with a* and b* a hair below zero, which would print as "-0.00"
+            let [l, a, b] = cie::rgb_to_lab(rgb).map(|v| if v.abs() < 0.005 { 0.0 } else { v });
+            format!("({:.2},{:.2},{:.2})", l, a, b)
+        }
+    }
+}
+
+/// Selects how fractional channel values are rounded when converting to integers
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
+pub enum RoundingMode {
+    /// Drops the fractional part, so `0.9` becomes `0`
+    Truncate,
+    /// Rounds to the nearest integer, with halves rounded up, so `0.5` becomes `1`.
+    /// This is what the crate's conversions use by default.
+    #[default]
+    HalfUp,
+    /// Rounds to the nearest integer, with halves rounded to the even neighbor, so `0.5` becomes
+    /// `0` and `1.5` becomes `2`
+    HalfEven,
+}
+
+impl RoundingMode {
+    /// Rounds a non-negative `f` to an integer-valued float according to this mode
+    pub fn round(self, f: f64) -> f64 {
+        match self {
+            RoundingMode::Truncate => f.trunc(),
+            RoundingMode::HalfUp => f.round(),
+            RoundingMode::HalfEven => f.round_ties_even(),
+        }
+    }
 }
 
 /// Represents a color, with RGB-255 as the "common" format for conversions
 pub trait Color: Display + Sized {
 
-    /// Converts this color into RGB-255
+    /// Converts this color into RGB-255, consuming `self`.
+    /// The default defers to [Color::as_rgb]; override it when owning `self` makes the
+    /// conversion cheaper, the way [RGB] simply returns itself.
     fn into_rgb(self) -> RGB {
         self.as_rgb()
     }
@@ -41,6 +249,32 @@ pub trait Color: Display + Sized {
     /// Converts this color into RGB-255, but without consuming `self`
     fn as_rgb(&self) -> RGB;
 
+    /// Returns an owned RGB-255 copy of this color, leaving `self` usable.
+    /// An alias for [Color::as_rgb] that reads better at call sites which keep the original.
+    fn to_owned_rgb(&self) -> RGB {
+        self.as_rgb()
+    }
+
+    /// Converts this color into RGB-255 with an alpha channel. The default is fully opaque;
+    /// [Alpha] overrides it to keep its stored alpha.
+    /// # Examples
+    /// ```
+    /// use color_changer::Color;
+    /// use color_changer::alpha::Alpha;
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::WHITE.to_rgba(), Alpha::opaque(RGB::WHITE));
+    /// ```
+    fn to_rgba(&self) -> Alpha<RGB> {
+        Alpha::opaque(self.as_rgb())
+    }
+
+    /// Converts this color into RGB with channels in `[0.0, 255.0]`, without rounding to `u8`.
+    /// The default just casts [Color::as_rgb]; types with more precision than RGB-255 override
+    /// this so precision-sensitive callers can skip the quantization.
+    fn as_rgb_f64(&self) -> [f64; 3] {
+        self.as_rgb().as_parts().map(|c| c as f64)
+    }
+
     /// Creates a color from the RGB representation.
     fn from_rgb(c: RGB) -> Self;
 
@@ -49,6 +283,32 @@ pub trait Color: Display + Sized {
         U::from_rgb(self.into_rgb())
     }
 
+    /// Generic conversion into any other color type, failing instead of silently losing
+    /// precision. The conversion is lossless when the converted color has exactly the same
+    /// RGB-255 representation as `self`; otherwise the error reports the largest channel change.
+    /// # Examples
+    /// ```
+    /// use color_changer::cmyk::CMYK;
+    /// use color_changer::rgb::RGB;
+    /// use color_changer::Color;
+    /// let cmyk: CMYK = RGB::new(0xED, 0xBB, 0xF3).try_into_color().unwrap();
+    /// assert_eq!(cmyk.as_rgb(), RGB::new(0xED, 0xBB, 0xF3));
+    /// ```
+    fn try_into_color<U: Color>(self) -> Result<U, LossyConversion> {
+        let rgb = self.into_rgb();
+        let converted = U::from_rgb(rgb);
+        let round_trip = converted.as_rgb();
+        if round_trip == rgb {
+            return Ok(converted);
+        }
+        let delta = rgb.as_parts().iter()
+            .zip(round_trip.as_parts().iter())
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap();
+        Err(LossyConversion { delta })
+    }
+
     /// Generic conversion into any other color type without consuming `self`
     fn as_color<U: Color>(&self) -> U {
         U::from_rgb(self.as_rgb())
@@ -59,13 +319,115 @@ pub trait Color: Display + Sized {
         Self::from_rgb(c.into_rgb())
     }
 
+    /// Returns whether this color and `other`, which may be of a different type, have exactly the
+    /// same RGB-255 representation
+    /// # Examples
+    /// ```
+    /// use color_changer::cmyk::CMYK;
+    /// use color_changer::rgb::RGB;
+    /// use color_changer::Color;
+    /// assert!(CMYK::WHITE.eq_rgb(&RGB::WHITE));
+    /// ```
+    fn eq_rgb(&self, other: &impl Color) -> bool {
+        self.as_rgb() == other.as_rgb()
+    }
+
+    /// Returns whether the RGB-255 representations of this color and `other`, which may be of a
+    /// different type, differ by at most `tolerance` in every channel
+    fn eq_rgb_within(&self, other: &impl Color, tolerance: u8) -> bool {
+        let a = self.as_rgb().as_parts();
+        let b = other.as_rgb().as_parts();
+        a.iter().zip(b.iter()).all(|(x, y)| x.abs_diff(*y) <= tolerance)
+    }
+
+    /// Computes the perceptual distance between two colors as the CIEDE2000 delta-E of their
+    /// L*a*b* representations. A distance below about `1.0` is generally imperceptible.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// use color_changer::Color;
+    /// assert!(RGB::BLACK.distance_to(&RGB::WHITE) > 99.0);
+    /// ```
+    fn distance_to(&self, other: &Self) -> f64 {
+        cie::delta_e_2000(cie::rgb_to_lab(self.as_rgb()), cie::rgb_to_lab(other.as_rgb()))
+    }
+
+    /// Returns this color with its CIE L*a*b* lightness `L*` set to `target`, in `[0.0, 100.0]`,
+    /// keeping `a*` and `b*`. This changes brightness more uniformly than HSL lightness does.
+    /// Results outside the RGB gamut are clamped per channel, which can shift the hue.
+    fn with_luminance(&self, target: f64) -> Self {
+        let [_, a, b] = cie::rgb_to_lab(self.as_rgb());
+        Self::from_rgb(cie::lab_to_rgb([target.clamp(0.0, 100.0), a, b]))
+    }
+
+    /// Treats this color as a foreground with opacity `alpha`, clamped to `[0.0, 1.0]`, over an
+    /// opaque `background`, blending the channels in RGB. The result is rounded to RGB-255
+    /// before converting back, so it may be lossy for types finer than RGB-255.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// use color_changer::Color;
+    /// assert_eq!(RGB::WHITE.composite_over(&RGB::BLACK, 0.5), RGB::new(0x80, 0x80, 0x80));
+    /// ```
+    fn composite_over(&self, background: &Self, alpha: f64) -> Self {
+        let alpha = alpha.clamp(0.0, 1.0);
+        let [fr, fg, fb] = self.as_rgb_f64();
+        let [br, bg, bb] = background.as_rgb_f64();
+        let mix = |f: f64, b: f64| f * alpha + b * (1.0 - alpha);
+        Self::from_rgb(RGB::from_floats_with_rounding(
+            mix(fr, br),
+            mix(fg, bg),
+            mix(fb, bb),
+            RoundingMode::default(),
+        ))
+    }
+
     /// Converts this color into the hex string of the RGB-255 representation.
     /// May be lossy.
     fn as_hex(&self) -> String {
         self.as_rgb().to_string()
     }
 
+    /// Formats this color as a 6-digit `RRGGBB` hex string like [Color::as_hex], with a name that
+    /// makes it explicit that anything beyond RGB-255, such as alpha, is dropped.
+    /// For alpha, see [Alpha::to_hex_rgba].
+    fn to_hex_rgb(&self) -> String {
+        self.as_hex()
+    }
+
+    /// Describes this color in every supported representation, one per line:
+    /// the hex string, CSS-style `rgb()`, then each other [ColorSpace] as formatted by [convert].
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// use color_changer::Color;
+    /// assert_eq!(
+    ///     RGB::WHITE.describe_all(),
+    ///     "hex: #FFFFFF\nrgb: rgb(255, 255, 255)\ncmyk: cmyk(0.00,0.00,0.00,0.00)\n\
+    ///      hsl: hsl(0.0,0.00,1.00)\nhsv: hsv(0.0,0.00,1.00)\nlab: lab(100.00,0.00,0.00)"
+    /// );
+    /// ```
+    fn describe_all(&self) -> String {
+        let rgb = self.as_rgb();
+        let mut lines = vec![format!("hex: #{}", rgb), format!("rgb: rgb({}, {}, {})", rgb.r, rgb.g, rgb.b)];
+        for &space in &ColorSpace::ALL {
+            let label = match space {
+                // Already covered by the hex line
+                ColorSpace::Rgb => continue,
+                ColorSpace::Cmyk => "cmyk",
+                ColorSpace::Hsl => "hsl",
+                ColorSpace::Hsv => "hsv",
+                ColorSpace::Lab => "lab",
+            };
+            lines.push(format!("{0}: {0}{1}", label, convert(rgb, space)));
+        }
+        lines.join("\n")
+    }
+
     /// Converts a hex string into whichever color representation is appropriate.
+    /// The string, ignoring surrounding whitespace, must be 3 or 6 hex digits with an optional
+    /// leading `#`. An 8-digit `#RRGGBBAA` string is also accepted, but its alpha channel is
+    /// silently dropped.
     /// # Examples
     /// ```
     /// use color_changer::rgb::RGB;
@@ -74,23 +436,289 @@ pub trait Color: Display + Sized {
     /// assert_eq!(black, RGB::BLACK);
     /// ```
     fn from_hex(s: impl AsRef<str>) -> Result<Self, ColorParseError> {
-        let matches = HEX_RE.captures(s.as_ref()).ok_or(BadInput)?;
-        let rgb: Result<Vec<u8>, ColorParseError> = matches.iter()
-            .skip(1)
-            .map(|x| x.unwrap())
-            .map(|m|m.as_str())
-            .map(|i| u8::from_str_radix(i, 16))
-            .try_fold(Vec::new(), |mut acc, i| {
-                acc.push(i?);
-                Ok(acc)
-            });
-        let rgb = rgb?;
-
-        if let [r, g, b] = &rgb[..] {
-            Ok(Self::from_rgb(RGB::new(*r, *g, *b)))
-        } else {
-            unreachable!()
+        let s = s.as_ref().trim();
+        let digits = s.strip_prefix('#').unwrap_or(s).chars().count();
+        if ![3, 6, 8].contains(&digits) {
+            return Err(ColorParseError::WrongLength { got: digits, expected: &[3, 6, 8] });
         }
+        let (rgb, _) = RGB::parse_hex_detailed(s)?;
+        Ok(Self::from_rgb(rgb))
+    }
+
+    /// Parses a hex string like [Color::from_hex], also returning whether the parsed color
+    /// converts back to exactly the RGB the hex string described.
+    /// `false` means the hex color can't be represented exactly in this color type.
+    /// # Examples
+    /// ```
+    /// use color_changer::cmyk::CMYK;
+    /// use color_changer::Color;
+    /// let (_, lossless) = CMYK::from_hex_checked("#808080").unwrap();
+    /// assert!(lossless);
+    /// ```
+    fn from_hex_checked(s: impl AsRef<str>) -> Result<(Self, bool), ColorParseError> {
+        let rgb = RGB::from_hex(s)?;
+        let color = Self::from_rgb(rgb);
+        let lossless = color.as_rgb() == rgb;
+        Ok((color, lossless))
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frgb::FRGB;
+
+    /// A deliberately lossy color type that keeps only the high nibble of each channel
+    #[derive(Debug, PartialEq)]
+    struct Nibbles(RGB);
+
+    impl Display for Nibbles {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    impl Color for Nibbles {
+        fn as_rgb(&self) -> RGB {
+            self.0
+        }
+
+        fn from_rgb(c: RGB) -> Self {
+            Nibbles(RGB::new(c.r & 0xF0, c.g & 0xF0, c.b & 0xF0))
+        }
+    }
+
+    #[test]
+    fn try_into_color() {
+        let vivid = RGB::new(0xED, 0xBB, 0xF3);
+        let grey = RGB::new(0x80, 0x80, 0x80);
+        assert_eq!(vivid.try_into_color::<Nibbles>(), Err(LossyConversion { delta: 0xD }));
+        assert_eq!(grey.try_into_color::<Nibbles>().unwrap(), Nibbles(grey));
+
+        // CMYK's u16 components hold every RGB-255 color exactly, so only lossy types fail
+        let cmyk: CMYK = vivid.try_into_color().unwrap();
+        assert_eq!(cmyk.as_rgb(), vivid);
+        assert!(CMYK::new(0.1, 0.5, 0.2, 0.3).try_into_color::<RGB>().is_ok());
+    }
+
+    #[test]
+    fn from_hex_checked() {
+        let (grey, lossless) = CMYK::from_hex_checked("#808080").unwrap();
+        assert!(lossless);
+        assert_eq!(grey, CMYK::from_hex("#808080").unwrap());
+        // CMYK's u16 components hold every RGB-255 color exactly
+        assert!(CMYK::from_hex_checked("#EDBBF3").unwrap().1);
+
+        assert!(Nibbles::from_hex_checked("#F0A020").unwrap().1);
+        let (vivid, lossless) = Nibbles::from_hex_checked("#EDBBF3").unwrap();
+        assert!(!lossless);
+        assert_eq!(vivid, Nibbles(RGB::new(0xE0, 0xB0, 0xF0)));
+        assert!(Nibbles::from_hex_checked("#12345").is_err());
+    }
+
+    #[test]
+    fn distance_to() {
+        let a = RGB::new(0xED, 0xBB, 0xF3);
+        let b = RGB::new(0x20, 0x80, 0x40);
+        assert!(a.distance_to(&a).abs() < 1e-9);
+        assert!((a.distance_to(&b) - b.distance_to(&a)).abs() < 1e-9);
+        assert!(a.distance_to(&b) > 10.0);
+
+        let c = CMYK::new(0.1, 0.5, 0.2, 0.3);
+        let d = CMYK::new(0.7, 0.1, 0.0, 0.1);
+        assert!(c.distance_to(&c).abs() < 1e-9);
+        assert!((c.distance_to(&d) - d.distance_to(&c)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn from_hex_wrong_length() {
+        for &(input, len) in &[("#FFFF", 4), ("FFFFF", 5), ("#FFFFFFF", 7), ("", 0), ("zzFFAABBzz", 10)] {
+            match RGB::from_hex(input) {
+                Err(ColorParseError::WrongLength { got, expected }) => {
+                    assert_eq!(got, len, "{}", input);
+                    assert_eq!(expected, &[3, 6, 8]);
+                }
+                other => panic!("{}: {:?}", input, other),
+            }
+        }
+        assert!(matches!(RGB::from_hex("#GGGGGG"), Err(ColorParseError::BadInput)));
+        assert_eq!(RGB::from_hex("#abc").unwrap(), RGB::new(0xAA, 0xBB, 0xCC));
+        assert_eq!(RGB::from_hex(" ABCDEF\n").unwrap(), RGB::new(0xAB, 0xCD, 0xEF));
+    }
+
+    #[test]
+    fn wrong_length_message() {
+        let error = RGB::from_hex("#FFFF").unwrap_err();
+        assert_eq!(error.to_string(), "Expected 3, 6 or 8 hex digits after an optional '#', but got 4");
+    }
+
+    #[test]
+    fn parse_failure_message() {
+        let error = CMYK::from_colon_percent("0:0:x:0").unwrap_err();
+        assert!(matches!(error, ColorParseError::ParseFailure(_)));
+        assert!(error.to_string().starts_with("A color component didn't parse: "), "{}", error);
+        assert!(!error.to_string().contains("hex"));
+    }
+
+    #[test]
+    fn from_hex_drops_alpha() {
+        assert_eq!(RGB::from_hex("#112233FF").unwrap(), RGB::new(0x11, 0x22, 0x33));
+        assert_eq!(RGB::from_hex("11223300").unwrap(), RGB::new(0x11, 0x22, 0x33));
+    }
+
+    #[test]
+    fn parse_hex_reader() {
+        let file = "\
+; palette exported by hand
+# Reds
+#FF0000
+  aa0000
+
+#!
+#00ff00
+zzzzzz
+";
+        let parsed: Vec<_> = super::parse_hex_reader(file.as_bytes()).collect();
+        assert_eq!(parsed.len(), 4);
+        assert_eq!(parsed[0].as_ref().unwrap(), &RGB::new(0xFF, 0, 0));
+        assert_eq!(parsed[1].as_ref().unwrap(), &RGB::new(0xAA, 0, 0));
+        assert_eq!(parsed[2].as_ref().unwrap(), &RGB::new(0, 0xFF, 0));
+        assert!(matches!(parsed[3], Err(ColorParseError::BadInput)));
+    }
+
+    #[test]
+    fn convert_at_runtime() {
+        let c = RGB::new(0xED, 0xBB, 0xF3);
+        let converted: Vec<String> = ColorSpace::ALL.iter().map(|&space| convert(c, space)).collect();
+        assert_eq!(converted, vec![
+            "EDBBF3",
+            "(0.02,0.23,0.00,0.05)",
+            "(293.6,0.70,0.84)",
+            "(293.6,0.23,0.95)",
+            "(81.85,27.33,-20.88)",
+        ]);
+        let red = RGB::new(0xFF, 0, 0);
+        assert_eq!(convert(red, ColorSpace::Hsl), "(0.0,1.00,0.50)");
+        assert_eq!(convert(red, ColorSpace::Hsv), "(0.0,1.00,1.00)");
+        assert_eq!(convert(RGB::WHITE, ColorSpace::Lab), "(100.00,0.00,0.00)");
+    }
+
+    #[test]
+    fn with_luminance() {
+        let warm = RGB::new(0xA0, 0x70, 0x50);
+        let cool = RGB::new(0x50, 0x70, 0xA0);
+        let warm = warm.with_luminance(60.0);
+        let cool = cool.with_luminance(60.0);
+        assert!((warm.relative_luminance() - cool.relative_luminance()).abs() < 0.005);
+        assert!((cie::rgb_to_lab(warm)[0] - 60.0).abs() < 0.5);
+        assert!(warm.r > warm.b && cool.b > cool.r);
+
+        assert_eq!(RGB::new(0x80, 0x80, 0x80).with_luminance(100.0), RGB::WHITE);
+        assert_eq!(CMYK::WHITE.with_luminance(0.0), CMYK::BLACK);
+    }
+
+    #[test]
+    fn eq_rgb() {
+        assert!(FRGB::new(1.0, 1.0, 1.0).eq_rgb(&CMYK::WHITE));
+        assert!(!FRGB::new(1.0, 1.0, 0.99).eq_rgb(&CMYK::WHITE));
+        assert!(FRGB::new(1.0, 1.0, 0.99).eq_rgb_within(&CMYK::WHITE, 3));
+        assert!(!CMYK::BLACK.eq_rgb_within(&RGB::new(4, 0, 0), 3));
+    }
+
+    #[test]
+    fn into_rgb_matches_as_rgb() {
+        fn check<C: Color>(c: C) {
+            let borrowed = c.as_rgb();
+            assert_eq!(c.to_owned_rgb(), borrowed, "{}", c);
+            assert_eq!(c.into_rgb(), borrowed);
+        }
+        check(RGB::new(0xED, 0xBB, 0xF3));
+        check(CMYK::new(0.1, 0.5, 0.2, 0.3));
+        check(FRGB::new(0.25, 0.5, 1.0));
+        check(alpha::Alpha::new(CMYK::new(0.7, 0.1, 0.0, 0.1), 0x80));
+    }
+
+    #[test]
+    fn to_rgba() {
+        assert_eq!(RGB::WHITE.to_rgba().alpha, 255);
+        assert_eq!(CMYK::BLACK.to_rgba(), Alpha::opaque(RGB::BLACK));
+        let translucent = Alpha::new(CMYK::WHITE, 0x40);
+        assert_eq!(translucent.to_rgba(), Alpha::new(RGB::WHITE, 0x40));
+    }
+
+    #[test]
+    fn to_hex_rgb() {
+        let translucent = Alpha::new(RGB::new(0xED, 0xBB, 0xF3), 0x80);
+        assert_eq!(translucent.as_hex(), "EDBBF3");
+        assert_eq!(translucent.to_hex_rgb(), "EDBBF3");
+        assert_eq!(translucent.to_hex_rgba(), "EDBBF380");
+        assert_eq!(CMYK::BLACK.to_hex_rgb(), "000000");
+    }
+
+    #[test]
+    fn composite_over() {
+        let fg = RGB::new(0xED, 0xBB, 0xF3);
+        let bg = RGB::new(0x20, 0x80, 0x40);
+        assert_eq!(fg.composite_over(&bg, 0.0), bg);
+        assert_eq!(fg.composite_over(&bg, 1.0), fg);
+        assert_eq!(fg.composite_over(&bg, 2.0), fg);
+        assert_eq!(RGB::WHITE.composite_over(&RGB::BLACK, 0.25), RGB::new(0x40, 0x40, 0x40));
+
+        let ink = CMYK::new(0.1, 0.5, 0.2, 0.3);
+        assert_eq!(ink.composite_over(&CMYK::WHITE, 0.0), CMYK::WHITE);
+        assert!(ink.composite_over(&CMYK::WHITE, 1.0).eq_rgb(&ink));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_errors() {
+        use serde_json::{json, to_value};
+        let parse_failure = u8::from_str_radix("zz", 16).unwrap_err();
+        let cases = [
+            (ColorParseError::BadInput, json!("BadInput")),
+            (
+                ColorParseError::WrongLength { got: 4, expected: &[3, 6, 8] },
+                json!({ "WrongLength": { "got": 4, "expected": [3, 6, 8] } }),
+            ),
+            (
+                ColorParseError::ParseFailure(parse_failure.clone()),
+                json!({ "ParseFailure": parse_failure.to_string() }),
+            ),
+            (
+                ColorParseError::WrongComponentCount { expected: 4, got: 3 },
+                json!({ "WrongComponentCount": { "expected": 4, "got": 3 } }),
+            ),
+            (ColorParseError::BadCss("no".to_string()), json!({ "BadCss": "no" })),
+            (
+                ColorParseError::OutOfRange { component: 'k', value: 101 },
+                json!({ "OutOfRange": { "component": "k", "value": 101 } }),
+            ),
+            (
+                ColorParseError::InkLimitExceeded { total: 3.5, max: 3.0 },
+                json!({ "InkLimitExceeded": { "total": 3.5, "max": 3.0 } }),
+            ),
+            (
+                ColorParseError::LengthMismatch { left: 3, right: 1 },
+                json!({ "LengthMismatch": { "left": 3, "right": 1 } }),
+            ),
+            (ColorParseError::ReadFailure("gone".to_string()), json!({ "ReadFailure": "gone" })),
+        ];
+        for (error, expected) in &cases {
+            assert_eq!(&to_value(error).unwrap(), expected, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn describe_all() {
+        let description = RGB::new(0xED, 0xBB, 0xF3).describe_all();
+        assert!(description.contains("#EDBBF3"));
+        assert!(description.contains("rgb(237, 187, 243)"));
+        assert!(description.contains("cmyk(0.02,0.23,0.00,0.05)"));
+        assert!(description.contains("\nhsl: hsl(293.6,0.70,0.84)"));
+        assert!(description.contains("\nhsv: hsv(293.6,0.23,0.95)"));
+        assert!(description.contains("\nlab: lab(81.85,27.33,-20.88)"));
+        assert_eq!(description.lines().count(), 6);
+        assert_eq!(description, CMYK::from_hex("#EDBBF3").unwrap().describe_all());
+    }
+}
@@ -0,0 +1,105 @@
+//! Macros exported at the crate root
+
+/// Defines `pub const` [RGB](crate::rgb::RGB) items from hex strings, evaluated at compile time
+/// with [RGB::from_hex_const](crate::rgb::RGB::from_hex_const).
+/// An invalid hex string fails compilation with an `invalid hex color` message.
+/// Attributes, including doc comments, are passed through to each constant.
+/// # Examples
+/// ```
+/// use color_changer::define_colors;
+/// use color_changer::rgb::RGB;
+/// define_colors! {
+///     /// The accent color
+///     ACCENT = "#FFAABB",
+///     BG = "#101010",
+/// }
+/// assert_eq!(ACCENT, RGB::new(0xFF, 0xAA, 0xBB));
+/// assert_eq!(BG, RGB::new(0x10, 0x10, 0x10));
+/// ```
+///
+/// ```compile_fail
+/// color_changer::define_colors! { BAD = "#12345" }
+/// ```
+#[macro_export]
+macro_rules! define_colors {
+    ($($(#[$meta:meta])* $name:ident = $hex:expr),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            pub const $name: $crate::rgb::RGB = $crate::rgb::RGB::from_hex_const($hex);
+        )*
+    };
+}
+
+/// Asserts that each [RGB](crate::rgb::RGB) value survives a round trip through a color type,
+/// i.e. that converting it with [Color::from_rgb](crate::Color::from_rgb) and back with
+/// [Color::as_rgb](crate::Color::as_rgb) moves no channel by more than the `u8` tolerance.
+/// Use it to check that a new color type meets the [Color](crate::Color) contract.
+/// # Examples
+/// ```
+/// use color_changer::assert_color_roundtrip;
+/// use color_changer::frgb::FRGB;
+/// use color_changer::rgb::RGB;
+/// assert_color_roundtrip!(FRGB, 0, [RGB::BLACK, RGB::WHITE, RGB::new(0xED, 0xBB, 0xF3)]);
+/// ```
+#[macro_export]
+macro_rules! assert_color_roundtrip {
+    ($t:ty, $tolerance:expr, [$($rgb:expr),* $(,)?]) => {
+        $({
+            let rgb: $crate::rgb::RGB = $rgb;
+            let converted = <$t as $crate::Color>::from_rgb(rgb);
+            let back = $crate::Color::as_rgb(&converted);
+            assert!(
+                $crate::Color::eq_rgb_within(&back, &rgb, $tolerance),
+                "{} round trip through {} gave {}, beyond tolerance {}",
+                rgb, stringify!($t), back, $tolerance
+            );
+        })*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rgb::RGB;
+
+    define_colors! {
+        ACCENT = "#FFAABB",
+        BG = "101010",
+        SHORT = "#abc"
+    }
+
+    #[test]
+    fn define_colors() {
+        assert_eq!(ACCENT, RGB::new(0xFF, 0xAA, 0xBB));
+        assert_eq!(BG, RGB::new(0x10, 0x10, 0x10));
+        assert_eq!(SHORT, RGB::new(0xAA, 0xBB, 0xCC));
+    }
+
+    #[test]
+    #[should_panic(expected = "beyond tolerance")]
+    fn assert_color_roundtrip_catches_loss() {
+        use crate::Color;
+
+        #[derive(Debug)]
+        struct Grey(u8);
+
+        impl std::fmt::Display for Grey {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "grey {}", self.0)
+            }
+        }
+
+        impl Color for Grey {
+            fn as_rgb(&self) -> RGB {
+                RGB::new(self.0, self.0, self.0)
+            }
+
+            fn from_rgb(c: RGB) -> Self {
+                Grey(c.to_grayscale_avg().r)
+            }
+        }
+
+        assert_color_roundtrip!(Grey, 0, [RGB::new(0x80, 0x80, 0x80)]);
+        assert_color_roundtrip!(Grey, 10, [RGB::new(0x80, 0x88, 0x80)]);
+        assert_color_roundtrip!(Grey, 10, [RGB::new(0xFF, 0, 0)]);
+    }
+}
@@ -0,0 +1,120 @@
+//! Operations over buffers of RGB-255 pixels
+
+use crate::rgb::RGB;
+use crate::ColorParseError;
+use std::collections::HashMap;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Counts how many times each color occurs in `pixels`
+/// # Examples
+/// ```
+/// use color_changer::rgb::RGB;
+/// use color_changer::pixels::histogram;
+/// let counts = histogram(&[RGB::BLACK, RGB::WHITE, RGB::BLACK]);
+/// assert_eq!(counts[&RGB::BLACK], 2);
+/// assert_eq!(counts[&RGB::WHITE], 1);
+/// ```
+pub fn histogram(pixels: &[RGB]) -> HashMap<RGB, usize> {
+    let mut counts = HashMap::new();
+    for &p in pixels {
+        *counts.entry(p).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Returns up to `n` of the most frequent colors in `pixels` with their counts, most frequent first.
+/// Colors with equal counts are ordered by their `[r, g, b]` components so the result is deterministic.
+pub fn dominant_colors(pixels: &[RGB], n: usize) -> Vec<(RGB, usize)> {
+    let mut counts: Vec<(RGB, usize)> = histogram(pixels).into_iter().collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| {
+        b_count.cmp(a_count).then_with(|| a.as_parts().cmp(&b.as_parts()))
+    });
+    counts.truncate(n);
+    counts
+}
+
+/// Produces an image of the per-channel absolute differences between `a` and `b`, so identical
+/// pixels are black. Fails with [ColorParseError::LengthMismatch] if the lengths differ.
+/// # Examples
+/// ```
+/// use color_changer::rgb::RGB;
+/// use color_changer::pixels::diff_image;
+/// let diff = diff_image(&[RGB::new(10, 20, 30)], &[RGB::new(15, 20, 25)]).unwrap();
+/// assert_eq!(diff, vec![RGB::new(5, 0, 5)]);
+/// ```
+pub fn diff_image(a: &[RGB], b: &[RGB]) -> Result<Vec<RGB>, ColorParseError> {
+    diff_image_amplified(a, b, 1.0)
+}
+
+/// Like [diff_image], but multiplies each difference by `gain` (saturating at `255`) so that
+/// small differences become visible
+pub fn diff_image_amplified(a: &[RGB], b: &[RGB], gain: f64) -> Result<Vec<RGB>, ColorParseError> {
+    if a.len() != b.len() {
+        return Err(ColorParseError::LengthMismatch { left: a.len(), right: b.len() });
+    }
+    let diff = |x: u8, y: u8| (x.abs_diff(y) as f64 * gain).round().clamp(0.0, 255.0) as u8;
+    let image = a.iter()
+        .zip(b.iter())
+        .map(|(p, q)| RGB::new(diff(p.r, q.r), diff(p.g, q.g), diff(p.b, q.b)))
+        .collect();
+    Ok(image)
+}
+
+/// Replaces each pixel `p` with `f(p)`, processing the buffer in parallel with rayon.
+/// Equivalent to `pixels.iter_mut().for_each(|p| *p = f(*p))`.
+/// Requires the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn par_transform(pixels: &mut [RGB], f: impl Fn(RGB) -> RGB + Sync) {
+    pixels.par_iter_mut().for_each(|p| *p = f(*p));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dominant() {
+        let red = RGB::new(0xFF, 0, 0);
+        let blue = RGB::new(0, 0, 0xFF);
+        let pixels = [red, blue, red, RGB::WHITE, red, blue, RGB::BLACK];
+        assert_eq!(histogram(&pixels).len(), 4);
+        assert_eq!(dominant_colors(&pixels, 2), vec![(red, 3), (blue, 2)]);
+        assert_eq!(
+            dominant_colors(&pixels, 10),
+            vec![(red, 3), (blue, 2), (RGB::BLACK, 1), (RGB::WHITE, 1)]
+        );
+        assert!(dominant_colors(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn diff() {
+        let a = [RGB::new(10, 20, 30), RGB::WHITE, RGB::BLACK];
+        assert_eq!(diff_image(&a, &a).unwrap(), vec![RGB::BLACK; 3]);
+
+        let mut b = a;
+        b[1].g = 250;
+        let diff = diff_image(&a, &b).unwrap();
+        assert_eq!(diff, vec![RGB::BLACK, RGB::new(0, 5, 0), RGB::BLACK]);
+        let amplified = diff_image_amplified(&a, &b, 100.0).unwrap();
+        assert_eq!(amplified[1], RGB::new(0, 255, 0));
+
+        assert!(matches!(
+            diff_image(&a, &a[..1]),
+            Err(ColorParseError::LengthMismatch { left: 3, right: 1 })
+        ));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_transform_matches_sequential() {
+        let invert = |c: RGB| RGB::new(!c.r, !c.g, !c.b);
+        let original: Vec<RGB> = (0..100_000u32)
+            .map(|i| RGB::new(i as u8, (i >> 8) as u8, (i * 7) as u8))
+            .collect();
+        let sequential: Vec<RGB> = original.iter().map(|&c| invert(c)).collect();
+        let mut parallel = original.clone();
+        par_transform(&mut parallel, invert);
+        assert_eq!(parallel, sequential);
+    }
+}
@@ -0,0 +1,162 @@
+//! Represents colors in the ITU-R BT.2020 (Rec. 2020) wide-gamut RGB space used for HDR and
+//! UHD video. Conversions go through CIE XYZ with the D65 white; converting down to [RGB]
+//! clamps colors that lie outside sRGB.
+
+use crate::cie::{rgb_to_xyz, xyz_to_linear_srgb, xyz_to_rgb};
+use crate::rgb::RGB;
+use crate::Color;
+use std::fmt::Display;
+
+/// The `α` constant of the BT.2020 transfer function
+const ALPHA: f64 = 1.09929682680944;
+/// The `β` constant of the BT.2020 transfer function, where the linear segment ends
+const BETA: f64 = 0.018053968510807;
+/// How far outside `[0.0, 1.0]` a linear sRGB component may stray and still count as in gamut,
+/// absorbing the rounding in the conversion matrices
+const GAMUT_TOLERANCE: f64 = 1e-4;
+
+/// A Rec. 2020 color with gamma-encoded `f64` components, each nominally in `[0.0, 1.0]`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rec2020 {
+    /// The red component
+    pub r: f64,
+    /// The green component
+    pub g: f64,
+    /// The blue component
+    pub b: f64,
+}
+
+impl Color for Rec2020 {
+    fn as_rgb(&self) -> RGB {
+        xyz_to_rgb(self.to_xyz())
+    }
+
+    fn as_rgb_f64(&self) -> [f64; 3] {
+        RGB::encode_linear(xyz_to_linear_srgb(self.to_xyz()))
+    }
+
+    fn from_rgb(c: RGB) -> Self {
+        let [x, y, z] = rgb_to_xyz(c);
+        let encode = |c: f64| {
+            let c = c.clamp(0.0, 1.0);
+            if c < BETA {
+                4.5 * c
+            } else {
+                ALPHA * c.powf(0.45) - (ALPHA - 1.0)
+            }
+        };
+        Rec2020::new(
+            encode(1.7166512 * x - 0.3556708 * y - 0.2533663 * z),
+            encode(-0.6666844 * x + 1.6164812 * y + 0.0157685 * z),
+            encode(0.0176399 * x - 0.0427706 * y + 0.9421031 * z),
+        )
+    }
+}
+
+impl Display for Rec2020 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({:.4},{:.4},{:.4})", self.r, self.g, self.b)
+    }
+}
+
+impl Rec2020 {
+    /// Creates a color from raw gamma-encoded components
+    pub const fn new(r: f64, g: f64, b: f64) -> Self {
+        Rec2020 { r, g, b }
+    }
+
+    /// Returns the components as an array, in order RGB
+    pub fn as_parts(&self) -> [f64; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    /// Returns whether this color can be shown in sRGB without clamping. Wide-gamut colors,
+    /// like a fully saturated Rec. 2020 green, return `false`.
+    /// # Examples
+    /// ```
+    /// use color_changer::Color;
+    /// use color_changer::rec2020::Rec2020;
+    /// use color_changer::rgb::RGB;
+    /// assert!(!Rec2020::new(0.0, 1.0, 0.0).is_in_srgb_gamut());
+    /// assert!(Rec2020::from_rgb(RGB::new(0, 0xFF, 0)).is_in_srgb_gamut());
+    /// ```
+    pub fn is_in_srgb_gamut(&self) -> bool {
+        xyz_to_linear_srgb(self.to_xyz())
+            .iter()
+            .all(|&c| (-GAMUT_TOLERANCE..=1.0 + GAMUT_TOLERANCE).contains(&c))
+    }
+
+    /// Converts into CIE XYZ, with `Y` in `[0.0, 1.0]`
+    fn to_xyz(self) -> [f64; 3] {
+        let decode = |c: f64| {
+            let c = c.clamp(0.0, 1.0);
+            if c < 4.5 * BETA {
+                c / 4.5
+            } else {
+                ((c + (ALPHA - 1.0)) / ALPHA).powf(1.0 / 0.45)
+            }
+        };
+        let [r, g, b] = self.as_parts().map(decode);
+        [
+            0.6369580 * r + 0.1446169 * g + 0.1688810 * b,
+            0.2627002 * r + 0.6779981 * g + 0.0593017 * b,
+            0.0280727 * g + 1.0609851 * b,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgb_roundtrip() {
+        crate::assert_color_roundtrip!(Rec2020, 0, [
+            RGB::BLACK,
+            RGB::WHITE,
+            RGB::new(0xFF, 0, 0),
+            RGB::new(0, 0xFF, 0),
+            RGB::new(0, 0, 0xFF),
+            RGB::new(0xED, 0xBB, 0xF3),
+            RGB::new(1, 2, 3),
+        ]);
+        for v in 0..=255u8 {
+            let c = RGB::new(v, 255 - v, v / 3);
+            assert_eq!(Rec2020::from_rgb(c).as_rgb(), c);
+        }
+    }
+
+    #[test]
+    fn white_and_primaries() {
+        let white = Rec2020::from_rgb(RGB::WHITE);
+        assert!(white.as_parts().iter().all(|c| (c - 1.0).abs() < 1e-3), "{}", white);
+        // sRGB's primaries sit inside Rec. 2020's, so they never reach full saturation here
+        let red = Rec2020::from_rgb(RGB::new(0xFF, 0, 0));
+        assert!(red.r < 1.0 && red.g > 0.0 && red.b > 0.0, "{}", red);
+    }
+
+    #[test]
+    fn as_rgb_f64() {
+        let c = Rec2020::from_rgb(RGB::new(0x80, 0x40, 0xC0));
+        let shifted = Rec2020::new(c.r + 0.001, c.g, c.b);
+        let [r, g, b] = shifted.as_rgb_f64();
+        assert!(r.fract() > 1e-3 && r.fract() < 1.0 - 1e-3, "{}", r);
+        assert_eq!(shifted.as_rgb(), RGB::new(r.round() as u8, g.round() as u8, b.round() as u8));
+        assert!(shifted.as_rgb_f64() != c.as_rgb_f64());
+
+        let clamped = Rec2020::new(0.0, 1.0, 0.0).as_rgb_f64();
+        assert!(clamped.iter().all(|c| (0.0..=255.0).contains(c)), "{:?}", clamped);
+    }
+
+    #[test]
+    fn out_of_gamut_green() {
+        let green = Rec2020::new(0.0, 1.0, 0.0);
+        assert!(!green.is_in_srgb_gamut());
+        assert_eq!(green.as_rgb(), RGB::new(0, 0xFF, 0));
+
+        assert!(Rec2020::new(0.5, 0.5, 0.5).is_in_srgb_gamut());
+        for &c in &[RGB::BLACK, RGB::WHITE, RGB::new(0, 0xFF, 0), RGB::new(0xFF, 0, 0xFF)] {
+            assert!(Rec2020::from_rgb(c).is_in_srgb_gamut(), "{}", c);
+        }
+    }
+}
@@ -1,10 +1,75 @@
 //! Contains datatypes and functions for manipulation and creation of RGB-255 colors
 
-use crate::Color;
+use crate::{cie, Color, ColorParseError, RoundingMode};
+use std::convert::TryFrom;
 use std::fmt::Display;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use once_cell::sync::Lazy;
+use regex::Regex;
 
-/// Represents an RGB-255 color.
+static DETAILED_HEX_RE: Lazy<Regex> = Lazy::new(
+    || Regex::new(r#"^(#?)([0-9a-fA-F]+)$"#).unwrap()
+);
+
+/// Names one channel of an [RGB] color
+#[derive(Copy, Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Channel {
+    /// The red channel
+    R,
+    /// The green channel
+    G,
+    /// The blue channel
+    B,
+}
+
+/// Describes the shape of a hex color string, as reported by [RGB::parse_hex_detailed]
 #[derive(Copy, Debug, Clone, Eq, PartialEq)]
+pub enum HexFormat {
+    /// 3 digits, e.g. `#ABC`
+    Short {
+        /// Whether the input had a leading `#`
+        hash: bool
+    },
+    /// 4 digits with a trailing alpha digit, e.g. `#ABCF`
+    ShortAlpha {
+        /// Whether the input had a leading `#`
+        hash: bool
+    },
+    /// 6 digits, e.g. `#AABBCC`
+    Long {
+        /// Whether the input had a leading `#`
+        hash: bool
+    },
+    /// 8 digits with trailing alpha digits, e.g. `#AABBCCFF`
+    LongAlpha {
+        /// Whether the input had a leading `#`
+        hash: bool
+    },
+}
+
+impl HexFormat {
+    /// Returns whether the input had a leading `#`
+    pub fn has_hash(&self) -> bool {
+        match *self {
+            HexFormat::Short { hash }
+            | HexFormat::ShortAlpha { hash }
+            | HexFormat::Long { hash }
+            | HexFormat::LongAlpha { hash } => hash,
+        }
+    }
+
+    /// Returns whether the input carried an alpha channel
+    pub fn has_alpha(&self) -> bool {
+        matches!(self, HexFormat::ShortAlpha { .. } | HexFormat::LongAlpha { .. })
+    }
+}
+
+/// Represents an RGB-255 color.
+///
+/// The struct is `#[repr(C)]`: the fields are laid out in the order `r`, `g`, `b` with no padding,
+/// so a `[RGB]` can be passed across an FFI boundary or reinterpreted as packed RGB pixel rows.
+#[derive(Copy, Debug, Clone, Eq, PartialEq, Hash)]
+#[repr(C)]
 pub struct RGB {
     /// The red component
     pub r: u8,
@@ -15,7 +80,12 @@ pub struct RGB {
 }
 
 impl Display for RGB {
+    /// Formats as a 6-digit uppercase hex string, e.g. `FFAABB`.
+    /// The alternate flag (`{:#}`) adds a leading `#`, e.g. `#FFAABB`.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            write!(f, "#")?;
+        }
         write!(f,
                "{:02X}{:02X}{:02X}",
                self.r,
@@ -30,7 +100,11 @@ impl Color for RGB {
     }
 
     fn as_rgb(&self) -> RGB {
-        self.clone()
+        *self
+    }
+
+    fn as_rgb_f64(&self) -> [f64; 3] {
+        self.as_parts().map(|c| c as f64)
     }
 
     fn from_rgb(c: RGB) -> Self {
@@ -39,6 +113,38 @@ impl Color for RGB {
 }
 
 
+impl Add for RGB {
+    type Output = RGB;
+
+    /// Adds the colors channel-wise, saturating at `255`
+    fn add(self, rhs: RGB) -> RGB {
+        RGB::new(self.r.saturating_add(rhs.r), self.g.saturating_add(rhs.g), self.b.saturating_add(rhs.b))
+    }
+}
+
+impl Sub for RGB {
+    type Output = RGB;
+
+    /// Subtracts the colors channel-wise, saturating at `0`
+    fn sub(self, rhs: RGB) -> RGB {
+        RGB::new(self.r.saturating_sub(rhs.r), self.g.saturating_sub(rhs.g), self.b.saturating_sub(rhs.b))
+    }
+}
+
+impl AddAssign for RGB {
+    /// Adds `rhs` channel-wise, saturating at `255`
+    fn add_assign(&mut self, rhs: RGB) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for RGB {
+    /// Subtracts `rhs` channel-wise, saturating at `0`
+    fn sub_assign(&mut self, rhs: RGB) {
+        *self = *self - rhs;
+    }
+}
+
 impl RGB {
     /// Black (`#000000`)
     pub const BLACK: RGB = RGB::new(0, 0,0);
@@ -50,6 +156,77 @@ impl RGB {
         RGB { r, g, b }
     }
 
+    /// Creates an RGB-255 color from wider integers, e.g. values read from JSON.
+    /// Fails with [ColorParseError::OutOfRange] naming the first channel outside `0..=255`.
+    pub fn try_from_i32(r: i32, g: i32, b: i32) -> Result<Self, ColorParseError> {
+        let channel = |component: char, value: i32| {
+            u8::try_from(value).map_err(|_| ColorParseError::OutOfRange { component, value: value as i64 })
+        };
+        Ok(RGB::new(channel('r', r)?, channel('g', g)?, channel('b', b)?))
+    }
+
+    /// Creates a color from an integer hex literal written as `0xRRGGBB`.
+    /// Only the low 24 bits are used, so any alpha or other data in the top byte is ignored.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// const ACCENT: RGB = RGB::from_hex_u32(0xFFAABB);
+    /// assert_eq!(ACCENT, RGB::new(0xFF, 0xAA, 0xBB));
+    /// ```
+    pub const fn from_hex_u32(v: u32) -> RGB {
+        RGB::new((v >> 16) as u8, (v >> 8) as u8, v as u8)
+    }
+
+    /// Parses a hex string in a `const` context, e.g. `"#FFAABB"`, `"ffaabb"` or `"#fab"`.
+    /// # Panics
+    /// Panics if the string isn't 3 or 6 hex digits with an optional leading `#`.
+    /// In a `const` item this becomes a compile error.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// const ACCENT: RGB = RGB::from_hex_const("#FFAABB");
+    /// assert_eq!(ACCENT, RGB::new(0xFF, 0xAA, 0xBB));
+    /// ```
+    pub const fn from_hex_const(s: &str) -> RGB {
+        const fn digit(c: u8) -> u8 {
+            match c {
+                b'0'..=b'9' => c - b'0',
+                b'a'..=b'f' => c - b'a' + 10,
+                b'A'..=b'F' => c - b'A' + 10,
+                _ => panic!("invalid hex color: found a character that isn't a hex digit"),
+            }
+        }
+        let bytes = s.as_bytes();
+        let start = if !bytes.is_empty() && bytes[0] == b'#' { 1 } else { 0 };
+        match bytes.len() - start {
+            3 => RGB::new(
+                digit(bytes[start]) * 0x11,
+                digit(bytes[start + 1]) * 0x11,
+                digit(bytes[start + 2]) * 0x11,
+            ),
+            6 => RGB::new(
+                digit(bytes[start]) << 4 | digit(bytes[start + 1]),
+                digit(bytes[start + 2]) << 4 | digit(bytes[start + 3]),
+                digit(bytes[start + 4]) << 4 | digit(bytes[start + 5]),
+            ),
+            _ => panic!("invalid hex color: expected 3 or 6 hex digits, optionally prefixed with '#'"),
+        }
+    }
+
+    /// Creates a color from float channels in `[0.0, 255.0]`, rounding them with `mode`.
+    /// Channels outside that range are clamped.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// use color_changer::RoundingMode;
+    /// let c = RGB::from_floats_with_rounding(0.5, 1.5, 254.9, RoundingMode::HalfEven);
+    /// assert_eq!(c, RGB::new(0, 2, 255));
+    /// ```
+    pub fn from_floats_with_rounding(r: f64, g: f64, b: f64, mode: RoundingMode) -> Self {
+        let conv = |f: f64| mode.round(f.clamp(0.0, 255.0)) as u8;
+        RGB::new(conv(r), conv(g), conv(b))
+    }
+
     /// Returns the raw bytes of the RGB color as an array, in order RGB
     /// # Examples
     /// ```
@@ -64,8 +241,726 @@ impl RGB {
         [self.r, self.g, self.b]
     }
 
+    /// Returns the bytes of this color as the 24-bit big-endian integer `0xRRGGBB`, i.e. `[r, g, b]`
+    pub const fn to_be_bytes(&self) -> [u8; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    /// Returns the bytes of this color as the 24-bit little-endian integer `0xRRGGBB`, i.e. `[b, g, r]`
+    pub const fn to_le_bytes(&self) -> [u8; 3] {
+        [self.b, self.g, self.r]
+    }
+
+    /// Creates a color from the bytes of a 24-bit big-endian `0xRRGGBB`, i.e. `[r, g, b]`
+    pub const fn from_be_bytes(bytes: [u8; 3]) -> Self {
+        let [r, g, b] = bytes;
+        RGB::new(r, g, b)
+    }
+
+    /// Creates a color from the bytes of a 24-bit little-endian `0xRRGGBB`, i.e. `[b, g, r]`
+    pub const fn from_le_bytes(bytes: [u8; 3]) -> Self {
+        let [b, g, r] = bytes;
+        RGB::new(r, g, b)
+    }
+
+    /// Returns the channels normalized to `[0.0, 1.0]`, as used by graphics APIs
+    pub fn to_f32_array(&self) -> [f32; 3] {
+        [self.r, self.g, self.b].map(|c| c as f32 / 255.0)
+    }
+
+    /// Creates a color from channels normalized to `[0.0, 1.0]`, clamping and rounding each
+    pub fn from_f32_array(parts: [f32; 3]) -> Self {
+        let [r, g, b] = parts.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        RGB::new(r, g, b)
+    }
+
+    /// Formats as the shortest lowercase hex string with a leading `#`: the 3-digit form when
+    /// every channel repeats its nibble, like `#abc` for `#AABBCC`, and 6 digits otherwise
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::new(0xAA, 0xBB, 0xCC).to_shortest_hex(), "#abc");
+    /// assert_eq!(RGB::new(0xAA, 0xBB, 0xCD).to_shortest_hex(), "#aabbcd");
+    /// ```
+    pub fn to_shortest_hex(&self) -> String {
+        if self.as_parts().iter().all(|c| c % 0x11 == 0) {
+            format!("#{:x}{:x}{:x}", self.r / 0x11, self.g / 0x11, self.b / 0x11)
+        } else {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        }
+    }
+
+    /// Formats as a CSS `rgb()` function with percentage channels, e.g. `rgb(100%, 0%, 50.2%)`.
+    /// Percentages are rounded to one decimal place, which is enough to recover every channel
+    /// exactly, and a trailing `.0` is omitted.
+    pub fn to_css_percent(&self) -> String {
+        let percent = |c: u8| {
+            let p = (c as f64 / 255.0 * 1000.0).round() / 10.0;
+            if p.fract() == 0.0 { format!("{:.0}%", p) } else { format!("{:.1}%", p) }
+        };
+        format!("rgb({}, {}, {})", percent(self.r), percent(self.g), percent(self.b))
+    }
+
+    /// Returns the value of the given channel
+    pub fn channel(&self, channel: Channel) -> u8 {
+        match channel {
+            Channel::R => self.r,
+            Channel::G => self.g,
+            Channel::B => self.b,
+        }
+    }
+
+    /// Rearranges the channels: each output channel, in order RGB, takes the value of the
+    /// corresponding channel named in `order`. Channels may be repeated.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::{RGB, Channel};
+    /// let bgr = RGB::new(1, 2, 3).swizzle([Channel::B, Channel::G, Channel::R]);
+    /// assert_eq!(bgr, RGB::new(3, 2, 1));
+    /// ```
+    pub fn swizzle(&self, order: [Channel; 3]) -> RGB {
+        let [r, g, b] = order;
+        RGB::new(self.channel(r), self.channel(g), self.channel(b))
+    }
+
     /// Returns whether or not this is equivalent to [RGB::BLACK]
     pub fn is_black(&self) -> bool {
         self.as_parts().iter().all(|&x| x == 0)
     }
-}
\ No newline at end of file
+
+    /// Returns whether every channel is at most `tolerance`, so `is_near_black(0)` is [RGB::is_black]
+    pub fn is_near_black(&self, tolerance: u8) -> bool {
+        self.as_parts().iter().all(|&x| x <= tolerance)
+    }
+
+    /// Converts to grey by taking the integer average `(r + g + b) / 3` of the channels.
+    /// This needs no floating point, so it is usable in `const` contexts, but it is perceptually
+    /// inferior to a luminance-weighted grey: pure green and pure blue map to the same grey even
+    /// though green appears far brighter.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// const GREY: RGB = RGB::new(30, 60, 90).to_grayscale_avg();
+    /// assert_eq!(GREY, RGB::new(60, 60, 60));
+    /// ```
+    pub const fn to_grayscale_avg(&self) -> RGB {
+        let avg = ((self.r as u16 + self.g as u16 + self.b as u16) / 3) as u8;
+        RGB::new(avg, avg, avg)
+    }
+
+    /// Packs this color into the 16-bit RGB565 format used by many embedded displays, with red in
+    /// the top 5 bits, green in the middle 6, and blue in the low 5.
+    /// This is lossy: the low 3 bits of red and blue and the low 2 bits of green are discarded.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::new(0xFF, 0, 0).to_rgb565(), 0xF800);
+    /// ```
+    pub fn to_rgb565(&self) -> u16 {
+        ((self.r as u16 >> 3) << 11) | ((self.g as u16 >> 2) << 5) | (self.b as u16 >> 3)
+    }
+
+    /// Expands a packed RGB565 value back into RGB-255.
+    /// The missing low bits of each channel are filled by replicating its high bits, so that
+    /// full-intensity channels expand to `0xFF` rather than `0xF8`.
+    pub fn from_rgb565(v: u16) -> RGB {
+        let r = ((v >> 11) & 0x1F) as u8;
+        let g = ((v >> 5) & 0x3F) as u8;
+        let b = (v & 0x1F) as u8;
+        RGB::new((r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2))
+    }
+
+    /// Reduces each channel to the given number of bits (`rb`, `gb`, `bb` for red, green and
+    /// blue), then expands back to 8 bits by replicating the kept high bits into the low bits,
+    /// so a full channel stays `0xFF`. Bit counts of 8 or more leave a channel unchanged, and 0
+    /// makes it `0`. `quantize_bits(5, 6, 5)` matches an RGB565 round trip.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::new(0xFF, 0x80, 0x7F).quantize_bits(1, 1, 1), RGB::new(0xFF, 0xFF, 0));
+    /// ```
+    pub fn quantize_bits(&self, rb: u8, gb: u8, bb: u8) -> RGB {
+        fn quantize(c: u8, bits: u8) -> u8 {
+            if bits == 0 {
+                return 0;
+            }
+            if bits >= 8 {
+                return c;
+            }
+            let kept = (c >> (8 - bits)) as u16;
+            let mut expanded = 0u16;
+            let mut filled = 0;
+            while filled < 8 {
+                expanded = (expanded << bits) | kept;
+                filled += bits;
+            }
+            (expanded >> (filled - 8)) as u8
+        }
+        RGB::new(quantize(self.r, rb), quantize(self.g, gb), quantize(self.b, bb))
+    }
+
+    /// Multiplies each channel by `factor`, directly on the gamma-encoded values.
+    /// Each product is rounded to the nearest integer (halves away from zero) and clamped to `[0, 255]`.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::new(10, 100, 200).scale(1.5), RGB::new(15, 150, 255));
+    /// ```
+    pub fn scale(&self, factor: f32) -> RGB {
+        let scale = |c: u8| (c as f32 * factor).round().clamp(0.0, 255.0) as u8;
+        RGB::new(scale(self.r), scale(self.g), scale(self.b))
+    }
+
+    /// Applies an independent power curve to each channel, e.g. to calibrate a display whose
+    /// channels respond differently: each channel `c` becomes `255 * (c / 255) ^ gamma`,
+    /// rounded and clamped to `[0, 255]`. A gamma above `1.0` darkens the channel, below `1.0`
+    /// brightens it, and `1.0` leaves it unchanged. Black and white channels never move.
+    ///
+    /// Returns `None` unless every gamma is finite and positive, since a zero or negative gamma
+    /// would turn black channels white and `NaN` would turn every channel black.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// let grey = RGB::new(0x80, 0x80, 0x80);
+    /// assert_eq!(grey.apply_channel_gamma(2.0, 1.0, 0.5), Some(RGB::new(0x40, 0x80, 0xB5)));
+    /// assert_eq!(grey.apply_channel_gamma(0.0, 1.0, 1.0), None);
+    /// ```
+    pub fn apply_channel_gamma(&self, gr: f64, gg: f64, gb: f64) -> Option<RGB> {
+        if ![gr, gg, gb].iter().all(|&gamma| gamma.is_finite() && gamma > 0.0) {
+            return None;
+        }
+        let curve = |c: u8, gamma: f64| (255.0 * (c as f64 / 255.0).powf(gamma)).round().clamp(0.0, 255.0) as u8;
+        Some(RGB::new(curve(self.r, gr), curve(self.g, gg), curve(self.b, gb)))
+    }
+
+    /// Returns whether `s` parses as a hex color equal to this one.
+    /// Strings that fail to parse never match.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert!(RGB::WHITE.matches_hex("#FFFFFF"));
+    /// assert!(!RGB::WHITE.matches_hex("not a color"));
+    /// ```
+    pub fn matches_hex(&self, s: &str) -> bool {
+        RGB::from_hex(s).is_ok_and(|c| c == *self)
+    }
+
+    /// Returns whether this color and `other` are indistinguishable to most viewers, i.e. their
+    /// CIEDE2000 [distance](Color::distance_to) is below `1.0`, the just-noticeable difference.
+    pub fn is_perceptually_equal(&self, other: &RGB) -> bool {
+        self.is_perceptually_equal_within(other, 1.0)
+    }
+
+    /// Returns whether the CIEDE2000 [distance](Color::distance_to) between this color and
+    /// `other` is below `jnd`
+    pub fn is_perceptually_equal_within(&self, other: &RGB, jnd: f64) -> bool {
+        self.distance_to(other) < jnd
+    }
+
+    /// Parses a hex string, also reporting which [HexFormat] it was written in so it can be
+    /// re-emitted in the same style. Unlike [Color::from_hex], surrounding whitespace is not
+    /// ignored and the 4-digit short form with alpha is accepted.
+    /// Any alpha digits are validated but dropped from the returned color.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::{RGB, HexFormat};
+    /// let (c, format) = RGB::parse_hex_detailed("#fa0").unwrap();
+    /// assert_eq!(c, RGB::new(0xFF, 0xAA, 0x00));
+    /// assert_eq!(format, HexFormat::Short { hash: true });
+    /// ```
+    pub fn parse_hex_detailed(s: &str) -> Result<(RGB, HexFormat), ColorParseError> {
+        let captures = DETAILED_HEX_RE.captures(s).ok_or(ColorParseError::BadInput)?;
+        let hash = !captures[1].is_empty();
+        let digits = &captures[2];
+        let (width, format) = match digits.len() {
+            3 => (1, HexFormat::Short { hash }),
+            4 => (1, HexFormat::ShortAlpha { hash }),
+            6 => (2, HexFormat::Long { hash }),
+            8 => (2, HexFormat::LongAlpha { hash }),
+            _ => return Err(ColorParseError::BadInput),
+        };
+        let channel = |i: usize| -> Result<u8, ColorParseError> {
+            let v = u8::from_str_radix(&digits[i * width..(i + 1) * width], 16)?;
+            Ok(if width == 1 { v * 0x11 } else { v })
+        };
+        Ok((RGB::new(channel(0)?, channel(1)?, channel(2)?), format))
+    }
+
+    /// Parses a hex string that may be broken up by spaces, dashes or colons, such as
+    /// `"FF AA BB"`, `"FF-AA-BB"` or `"#FF:AA:BB"`. The separators are removed and the rest is
+    /// parsed like [Color::from_hex], which stays strict.
+    pub fn from_hex_loose(s: &str) -> Result<RGB, ColorParseError> {
+        let stripped: String = s.chars().filter(|c| !matches!(c, ' ' | '-' | ':')).collect();
+        RGB::from_hex(stripped)
+    }
+
+    /// Returns how colorful this color looks, using the Hasler–Süsstrunk colorfulness metric on
+    /// the opponent channels `r - g` and `(r + g) / 2 - b`. For a single color the metric's
+    /// spread term vanishes, leaving `0.3` times the opponent magnitude. Greys score `0.0`;
+    /// the most vivid colors, such as pure red, score around `85`.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::new(0x80, 0x80, 0x80).vibrance(), 0.0);
+    /// assert!(RGB::new(0xFF, 0, 0).vibrance() > RGB::new(0xC0, 0x80, 0x80).vibrance());
+    /// ```
+    pub fn vibrance(&self) -> f64 {
+        let [r, g, b] = [self.r as f64, self.g as f64, self.b as f64];
+        let rg = r - g;
+        let yb = 0.5 * (r + g) - b;
+        0.3 * rg.hypot(yb)
+    }
+
+    /// Returns whether this color is grey within `tolerance`, i.e. the spread between its largest
+    /// and smallest channels is at most `tolerance`
+    pub fn is_near_grey(&self, tolerance: u8) -> bool {
+        let parts = self.as_parts();
+        let max = parts.iter().max().unwrap();
+        let min = parts.iter().min().unwrap();
+        max - min <= tolerance
+    }
+
+    /// Returns the grey with the same [relative luminance](RGB::relative_luminance) as this color
+    /// (up to rounding), for collapsing near-greys like `RGB::new(127, 128, 127)` to true greys.
+    /// Pair it with [RGB::is_near_grey] to leave saturated colors alone.
+    pub fn snap_to_grey(&self) -> RGB {
+        self.matching_gray()
+    }
+
+    /// Returns the grey that looks as light as this color, e.g. for duotone effects.
+    /// Its channel value is solved so that its [relative luminance](RGB::relative_luminance)
+    /// matches this color's, up to rounding, which weights green far above blue unlike
+    /// [RGB::to_grayscale_avg]. Greys return themselves.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::new(0, 0xFF, 0).matching_gray(), RGB::new(0xDC, 0xDC, 0xDC));
+    /// assert_eq!(RGB::new(0, 0, 0xFF).matching_gray(), RGB::new(0x4C, 0x4C, 0x4C));
+    /// ```
+    pub fn matching_gray(&self) -> RGB {
+        let y = self.relative_luminance();
+        RGB::from_linear([y, y, y])
+    }
+
+    /// Returns whether the hex string `s` has a leading `#`, or `None` if it isn't a valid hex
+    /// color for [RGB::parse_hex_detailed]. Useful for echoing input back in the same style.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// assert_eq!(RGB::hex_had_hash("#abc"), Some(true));
+    /// assert_eq!(RGB::hex_had_hash("abc"), Some(false));
+    /// assert_eq!(RGB::hex_had_hash("#xyz"), None);
+    /// ```
+    pub fn hex_had_hash(s: &str) -> Option<bool> {
+        RGB::parse_hex_detailed(s).ok().map(|(_, format)| format.has_hash())
+    }
+
+    /// Returns this color's CIE xyY coordinates `(x, y, Y)`, for plotting on a chromaticity
+    /// diagram: `x` and `y` are the chromaticity and `Y`, in `[0.0, 1.0]`, the luminance.
+    /// Black has no chromaticity, so it gets the D65 white point's `(0.3127, 0.3290)` with `Y = 0`.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// let (x, y, _) = RGB::new(0xFF, 0, 0).to_xyy();
+    /// assert!((x - 0.64).abs() < 1e-3 && (y - 0.33).abs() < 1e-3);
+    /// ```
+    pub fn to_xyy(&self) -> (f64, f64, f64) {
+        let xyz = cie::rgb_to_xyz(*self);
+        let [x, y, z] = if self.is_black() { cie::rgb_to_xyz(RGB::WHITE) } else { xyz };
+        let sum = x + y + z;
+        (x / sum, y / sum, xyz[1])
+    }
+
+    /// Returns the linear-light components of this color in `[0.0, 1.0]`, removing the sRGB transfer curve
+    pub(crate) fn as_linear(&self) -> [f64; 3] {
+        let decode = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        [decode(self.r), decode(self.g), decode(self.b)]
+    }
+
+    /// Creates a color from linear-light components, applying the sRGB transfer curve.
+    /// Components are clamped to `[0.0, 1.0]` first.
+    pub(crate) fn from_linear(parts: [f64; 3]) -> Self {
+        let [r, g, b] = RGB::encode_linear(parts).map(|c| c.round() as u8);
+        RGB::new(r, g, b)
+    }
+
+    /// Applies the sRGB transfer curve to linear-light components like [RGB::from_linear], but
+    /// returns the channels in `[0.0, 255.0]` without rounding
+    pub(crate) fn encode_linear(parts: [f64; 3]) -> [f64; 3] {
+        parts.map(|c| {
+            let c = c.clamp(0.0, 1.0);
+            let c = if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            };
+            c * 255.0
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::{align_of, offset_of, size_of};
+
+    #[test]
+    fn apply_channel_gamma() {
+        let c = RGB::new(0x40, 0x80, 0xC0);
+        assert_eq!(c.apply_channel_gamma(1.0, 1.0, 1.0), Some(c));
+
+        let red_only = c.apply_channel_gamma(2.2, 1.0, 1.0).unwrap();
+        assert!(red_only.r < c.r);
+        assert_eq!((red_only.g, red_only.b), (c.g, c.b));
+
+        let grey = RGB::new(0x80, 0x80, 0x80).apply_channel_gamma(0.45, 0.45, 0.45).unwrap();
+        assert!(grey.r > 0x80 && grey.r == grey.g && grey.g == grey.b);
+        assert_eq!(RGB::WHITE.apply_channel_gamma(3.0, 0.2, 1.5), Some(RGB::WHITE));
+        assert_eq!(RGB::BLACK.apply_channel_gamma(3.0, 0.2, 1.5), Some(RGB::BLACK));
+
+        for &bad in &[0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(RGB::BLACK.apply_channel_gamma(bad, 1.0, 1.0), None, "{}", bad);
+            assert_eq!(c.apply_channel_gamma(1.0, 1.0, bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn to_shortest_hex() {
+        assert_eq!(RGB::new(0xAA, 0xBB, 0xCC).to_shortest_hex(), "#abc");
+        assert_eq!(RGB::new(0xAA, 0xBB, 0xCD).to_shortest_hex(), "#aabbcd");
+        assert_eq!(RGB::BLACK.to_shortest_hex(), "#000");
+        assert_eq!(RGB::WHITE.to_shortest_hex(), "#fff");
+        assert_eq!(RGB::new(0x01, 0x02, 0x03).to_shortest_hex(), "#010203");
+        for &c in &[RGB::new(0x11, 0x22, 0x33), RGB::new(0x12, 0x34, 0x56)] {
+            assert_eq!(RGB::from_hex(c.to_shortest_hex()).unwrap(), c);
+        }
+    }
+
+    #[test]
+    fn to_xyy() {
+        let primaries = [
+            (RGB::new(0xFF, 0, 0), 0.64, 0.33),
+            (RGB::new(0, 0xFF, 0), 0.30, 0.60),
+            (RGB::new(0, 0, 0xFF), 0.15, 0.06),
+            (RGB::WHITE, 0.3127, 0.3290),
+        ];
+        for &(c, x, y) in &primaries {
+            let (cx, cy, _) = c.to_xyy();
+            assert!((cx - x).abs() < 1e-3 && (cy - y).abs() < 1e-3, "{}: {} {}", c, cx, cy);
+        }
+        assert!((RGB::WHITE.to_xyy().2 - 1.0).abs() < 1e-6);
+
+        let (x, y, luminance) = RGB::BLACK.to_xyy();
+        assert_eq!(luminance, 0.0);
+        assert_eq!((x, y), (RGB::WHITE.to_xyy().0, RGB::WHITE.to_xyy().1));
+        let (gx, gy, _) = RGB::new(0x40, 0x40, 0x40).to_xyy();
+        assert!((gx - x).abs() < 1e-9 && (gy - y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn from_hex_u32() {
+        assert_eq!(RGB::from_hex_u32(0xFFAABB), RGB::new(0xFF, 0xAA, 0xBB));
+        assert_eq!(RGB::from_hex_u32(0x80FFAABB), RGB::new(0xFF, 0xAA, 0xBB));
+        assert_eq!(RGB::from_hex_u32(0), RGB::BLACK);
+        assert_eq!(RGB::from_hex_u32(0xFFAABB), RGB::from_hex_const("#FFAABB"));
+    }
+
+    #[test]
+    fn from_hex_loose() {
+        let c = RGB::new(0xFF, 0xAA, 0xBB);
+        for input in &["FF AA BB", "FF-AA-BB", "ff:aa:bb", "#FF AA BB", " FFAABB ", "F-A-B"] {
+            assert_eq!(RGB::from_hex_loose(input).unwrap(), c, "{}", input);
+        }
+        assert!(RGB::from_hex("FF AA BB").is_err());
+        assert!(RGB::from_hex_loose("FF_AA_BB").is_err());
+        assert!(matches!(RGB::from_hex_loose("FF-AA"), Err(ColorParseError::WrongLength { got: 4, .. })));
+    }
+
+    #[test]
+    fn vibrance() {
+        for &v in &[0, 0x80, 0xFF] {
+            assert_eq!(RGB::new(v, v, v).vibrance(), 0.0);
+        }
+        let red = RGB::new(0xFF, 0, 0).vibrance();
+        assert!((red - 85.5).abs() < 0.1, "{}", red);
+        assert!(RGB::new(0, 0, 0xFF).vibrance() > RGB::new(0x60, 0x60, 0x80).vibrance());
+        assert!(RGB::new(0x82, 0x80, 0x80).vibrance() < 1.0);
+    }
+
+    #[test]
+    fn try_from_i32() {
+        assert_eq!(RGB::try_from_i32(0, 128, 255).unwrap(), RGB::new(0, 128, 255));
+        assert!(matches!(
+            RGB::try_from_i32(10, -1, 20),
+            Err(ColorParseError::OutOfRange { component: 'g', value: -1 })
+        ));
+        assert!(matches!(
+            RGB::try_from_i32(10, 20, 256),
+            Err(ColorParseError::OutOfRange { component: 'b', value: 256 })
+        ));
+        assert!(matches!(
+            RGB::try_from_i32(300, -5, 20),
+            Err(ColorParseError::OutOfRange { component: 'r', value: 300 })
+        ));
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let round = |f: f64, mode| RGB::from_floats_with_rounding(f, f, f, mode).r;
+        assert_eq!(round(0.5, RoundingMode::Truncate), 0);
+        assert_eq!(round(0.5, RoundingMode::HalfUp), 1);
+        assert_eq!(round(0.5, RoundingMode::HalfEven), 0);
+        assert_eq!(round(1.5, RoundingMode::HalfEven), 2);
+        assert_eq!(round(2.5, RoundingMode::HalfEven), 2);
+        assert_eq!(round(0.9, RoundingMode::Truncate), 0);
+        assert_eq!(round(300.0, RoundingMode::HalfUp), 255);
+        assert_eq!(round(-4.0, RoundingMode::Truncate), 0);
+    }
+
+    #[test]
+    fn f32_arrays() {
+        assert_eq!(RGB::WHITE.to_f32_array(), [1.0; 3]);
+        assert_eq!(RGB::BLACK.to_f32_array(), [0.0; 3]);
+        for v in 0..=255u8 {
+            let c = RGB::new(v, 255 - v, v / 3);
+            assert_eq!(RGB::from_f32_array(c.to_f32_array()), c);
+        }
+        assert_eq!(RGB::from_f32_array([1.5, -0.5, 0.5]), RGB::new(255, 0, 128));
+    }
+
+    #[test]
+    fn byte_order() {
+        let c = RGB::new(0x11, 0x22, 0x33);
+        assert_eq!(c.to_be_bytes(), [0x11, 0x22, 0x33]);
+        assert_eq!(c.to_le_bytes(), [0x33, 0x22, 0x11]);
+        assert_eq!(RGB::from_be_bytes(c.to_be_bytes()), c);
+        assert_eq!(RGB::from_le_bytes(c.to_le_bytes()), c);
+        assert_eq!(&0x112233u32.to_le_bytes()[..3], &c.to_le_bytes()[..]);
+    }
+
+    #[test]
+    fn hex_had_hash() {
+        assert_eq!(RGB::hex_had_hash("#abc"), Some(true));
+        assert_eq!(RGB::hex_had_hash("abc"), Some(false));
+        assert_eq!(RGB::hex_had_hash("#AABBCCDD"), Some(true));
+        assert_eq!(RGB::hex_had_hash("garbage"), None);
+        assert_eq!(RGB::hex_had_hash("#"), None);
+    }
+
+    #[test]
+    fn swizzle() {
+        use Channel::*;
+        let c = RGB::new(0x11, 0x22, 0x33);
+        assert_eq!(c.swizzle([R, G, B]), c);
+        assert_eq!(c.swizzle([B, G, R]), RGB::new(0x33, 0x22, 0x11));
+        assert_eq!(c.swizzle([B, G, R]).swizzle([B, G, R]), c);
+        assert_eq!(c.swizzle([G, G, G]), RGB::new(0x22, 0x22, 0x22));
+    }
+
+    #[test]
+    fn near_black() {
+        assert!(RGB::BLACK.is_near_black(0));
+        assert!(!RGB::new(1, 0, 0).is_near_black(0));
+        assert!(RGB::new(1, 0, 2).is_near_black(2));
+        assert!(!RGB::new(3, 0, 2).is_near_black(2));
+    }
+
+    #[test]
+    fn near_grey() {
+        let near = RGB::new(127, 128, 127);
+        assert!(near.is_near_grey(1));
+        assert!(!near.is_near_grey(0));
+        assert_eq!(near.snap_to_grey(), RGB::new(128, 128, 128));
+        assert_eq!(RGB::new(0x42, 0x42, 0x42).snap_to_grey(), RGB::new(0x42, 0x42, 0x42));
+
+        let saturated = RGB::new(0xFF, 0x20, 0x20);
+        assert!(!saturated.is_near_grey(10));
+        let cleaned = if saturated.is_near_grey(10) { saturated.snap_to_grey() } else { saturated };
+        assert_eq!(cleaned, saturated);
+    }
+
+    #[test]
+    fn matching_gray() {
+        for &c in &[RGB::new(0xED, 0xBB, 0xF3), RGB::new(0xFF, 0, 0), RGB::new(0x20, 0x80, 0x40), RGB::new(1, 2, 3)] {
+            let grey = c.matching_gray();
+            assert!(grey.r == grey.g && grey.g == grey.b, "{}", grey);
+            assert!((grey.relative_luminance() - c.relative_luminance()).abs() < 0.005, "{}: {}", c, grey);
+            assert!((grey.contrast_ratio(&c) - 1.0).abs() < 0.02);
+        }
+        for v in 0..=255u8 {
+            let grey = RGB::new(v, v, v);
+            assert_eq!(grey.matching_gray(), grey);
+        }
+    }
+
+    #[test]
+    fn grayscale_avg() {
+        const WHITE: RGB = RGB::WHITE.to_grayscale_avg();
+        assert_eq!(WHITE, RGB::WHITE);
+        assert_eq!(RGB::new(1, 1, 2).to_grayscale_avg(), RGB::new(1, 1, 1));
+
+        // Rec. 601 luma weights, for comparison with the unweighted average
+        let weighted = |c: RGB| (0.299 * c.r as f64 + 0.587 * c.g as f64 + 0.114 * c.b as f64).round() as u8;
+        let green = RGB::new(0, 0xFF, 0);
+        let blue = RGB::new(0, 0, 0xFF);
+        assert_eq!(green.to_grayscale_avg(), blue.to_grayscale_avg());
+        assert!(weighted(green) > weighted(blue));
+        let grey = RGB::new(0x80, 0x80, 0x80);
+        assert_eq!(grey.to_grayscale_avg().r, weighted(grey));
+    }
+
+    #[test]
+    fn rgb565() {
+        assert_eq!(RGB::new(0xFF, 0, 0).to_rgb565(), 0xF800);
+        assert_eq!(RGB::new(0, 0xFF, 0).to_rgb565(), 0x07E0);
+        assert_eq!(RGB::new(0, 0, 0xFF).to_rgb565(), 0x001F);
+        assert_eq!(RGB::from_rgb565(0xFFFF), RGB::WHITE);
+        assert_eq!(RGB::from_rgb565(0), RGB::BLACK);
+
+        for v in (0..=255u8).step_by(5) {
+            let c = RGB::new(v, v.wrapping_mul(7), 255 - v);
+            let back = RGB::from_rgb565(c.to_rgb565());
+            assert!(c.r.abs_diff(back.r) < 8 && c.b.abs_diff(back.b) < 8, "{:?} {:?}", c, back);
+            assert!(c.g.abs_diff(back.g) < 4, "{:?} {:?}", c, back);
+            assert_eq!(back.to_rgb565(), c.to_rgb565());
+        }
+    }
+
+    #[test]
+    fn quantize_bits() {
+        for v in 0..=255u8 {
+            let c = RGB::new(v, v.wrapping_mul(3), 255 - v);
+            assert_eq!(c.quantize_bits(5, 6, 5), RGB::from_rgb565(c.to_rgb565()));
+            assert_eq!(c.quantize_bits(8, 8, 8), c);
+            assert_eq!(c.quantize_bits(0, 0, 0), RGB::BLACK);
+        }
+        assert_eq!(RGB::new(0xB6, 0, 0).quantize_bits(3, 3, 3).r, 0xB6);
+        assert_eq!(RGB::WHITE.quantize_bits(3, 2, 7), RGB::WHITE);
+    }
+
+    #[test]
+    fn scale() {
+        let c = RGB::new(0x40, 0x80, 0xC0);
+        assert_eq!(c.scale(2.0), RGB::new(0x80, 0xFF, 0xFF));
+        assert_eq!(c.scale(0.0), RGB::BLACK);
+        assert_eq!(c.scale(1.0), c);
+        assert_eq!(RGB::new(1, 3, 5).scale(0.5), RGB::new(1, 2, 3));
+        assert_eq!(c.scale(-1.0), RGB::BLACK);
+    }
+
+    #[test]
+    fn from_hex_const() {
+        const C: RGB = RGB::from_hex_const("#EDBBF3");
+        assert_eq!(C, RGB::from_hex("#EDBBF3").unwrap());
+        assert_eq!(RGB::from_hex_const("edbbf3"), C);
+        assert_eq!(RGB::from_hex_const("#fA0"), RGB::new(0xFF, 0xAA, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid hex color")]
+    fn from_hex_const_invalid() {
+        RGB::from_hex_const("#EDBBF");
+    }
+
+    #[test]
+    fn perceptually_equal() {
+        let c = RGB::new(0x80, 0x40, 0xC0);
+        assert!(c.is_perceptually_equal(&c));
+        assert!(c.is_perceptually_equal(&RGB::new(0x81, 0x40, 0xC0)));
+        assert!(c.is_perceptually_equal(&RGB::new(0x80, 0x40, 0xBF)));
+        assert!(!c.is_perceptually_equal(&RGB::new(0x40, 0x80, 0xC0)));
+        assert!(!c.is_perceptually_equal_within(&RGB::new(0x84, 0x40, 0xC0), 0.5));
+        assert!(c.is_perceptually_equal_within(&RGB::new(0x84, 0x40, 0xC0), 5.0));
+    }
+
+    #[test]
+    fn saturating_arithmetic() {
+        let mut acc = RGB::BLACK;
+        for c in &[RGB::new(100, 10, 0), RGB::new(100, 20, 0), RGB::new(100, 30, 1)] {
+            acc += *c;
+        }
+        assert_eq!(acc, RGB::new(255, 60, 1));
+        acc -= RGB::new(5, 100, 0);
+        assert_eq!(acc, RGB::new(250, 0, 1));
+        assert_eq!(RGB::WHITE + RGB::WHITE, RGB::WHITE);
+        assert_eq!(RGB::BLACK - RGB::WHITE, RGB::BLACK);
+    }
+
+    #[test]
+    fn css_percent() {
+        assert_eq!(RGB::WHITE.to_css_percent(), "rgb(100%, 100%, 100%)");
+        assert_eq!(RGB::BLACK.to_css_percent(), "rgb(0%, 0%, 0%)");
+        assert_eq!(RGB::new(255, 0, 128).to_css_percent(), "rgb(100%, 0%, 50.2%)");
+
+        let parse = |s: &str| -> Vec<u8> {
+            s.trim_start_matches("rgb(")
+                .trim_end_matches(')')
+                .split(", ")
+                .map(|p| (p.trim_end_matches('%').parse::<f64>().unwrap() / 100.0 * 255.0).round() as u8)
+                .collect()
+        };
+        for v in 0..=255u8 {
+            let c = RGB::new(v, 255 - v, v / 2);
+            assert_eq!(parse(&c.to_css_percent()), c.as_parts());
+        }
+    }
+
+    #[test]
+    fn display() {
+        let c = RGB::new(0xFF, 0xAA, 0x0B);
+        assert_eq!(format!("{}", c), "FFAA0B");
+        assert_eq!(format!("{:#}", c), "#FFAA0B");
+        assert_eq!(c.as_hex(), "FFAA0B");
+    }
+
+    #[test]
+    fn matches_hex() {
+        let c = RGB::new(0xED, 0xBB, 0xF3);
+        assert!(c.matches_hex("#EDBBF3"));
+        assert!(c.matches_hex("edbbf3"));
+        assert!(!c.matches_hex("#EDBBF4"));
+        assert!(!c.matches_hex("#GGGGGG"));
+        assert!(!c.matches_hex(""));
+    }
+
+    #[test]
+    fn parse_hex_detailed() {
+        let c = RGB::new(0xAA, 0xBB, 0xCC);
+        let cases = [
+            ("abc", HexFormat::Short { hash: false }),
+            ("#abc", HexFormat::Short { hash: true }),
+            ("ABCF", HexFormat::ShortAlpha { hash: false }),
+            ("#abc0", HexFormat::ShortAlpha { hash: true }),
+            ("AABBCC", HexFormat::Long { hash: false }),
+            ("#aabbcc", HexFormat::Long { hash: true }),
+            ("aabbcc80", HexFormat::LongAlpha { hash: false }),
+            ("#AABBCCFF", HexFormat::LongAlpha { hash: true }),
+        ];
+        for &(input, format) in &cases {
+            assert_eq!(RGB::parse_hex_detailed(input).unwrap(), (c, format), "{}", input);
+        }
+        assert!(HexFormat::LongAlpha { hash: true }.has_alpha());
+        assert!(!HexFormat::Short { hash: false }.has_hash());
+
+        for &bad in &["", "#", "ab", "abcde", "#aabbccd", "##abc", "xyz", " abc"] {
+            assert!(RGB::parse_hex_detailed(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn rgb_layout() {
+        assert_eq!(size_of::<RGB>(), 3);
+        assert_eq!(align_of::<RGB>(), 1);
+        assert_eq!(size_of::<[RGB; 4]>(), 12);
+        assert_eq!(offset_of!(RGB, r), 0);
+        assert_eq!(offset_of!(RGB, g), 1);
+        assert_eq!(offset_of!(RGB, b), 2);
+    }
+}
@@ -0,0 +1,140 @@
+//! Color scheme generation from a single seed color, using hue rotations and lightness
+//! variations in HSL, and lightness ramps in CIELAB
+
+use crate::cie::{lab_to_rgb, rgb_to_lab};
+use crate::hue::{hsl_to_rgb, rgb_to_hsl};
+use crate::rgb::RGB;
+
+/// The CIELAB lightness of the darkest and lightest colors in an [RGB::mono_ramp]
+const RAMP_LIGHTNESS: (f64, f64) = (15.0, 95.0);
+/// The fraction of the seed's chroma left at either end of an [RGB::mono_ramp]
+const RAMP_END_CHROMA: f64 = 0.2;
+
+/// The kinds of color scheme [RGB::generate_scheme] can produce
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SchemeKind {
+    /// The seed with two darker shades and two lighter tints of the same hue
+    Monochromatic,
+    /// The seed and the color opposite it on the hue wheel
+    Complementary,
+    /// Three colors evenly spaced 120° apart on the hue wheel
+    Triadic,
+    /// The seed and its neighbors 30° to either side on the hue wheel
+    Analogous,
+    /// Four colors evenly spaced 90° apart on the hue wheel
+    Tetradic,
+}
+
+impl RGB {
+    /// Generates a small set of coordinated colors from this seed color.
+    /// The seed itself is always the first color. Hue-based schemes keep the seed's saturation
+    /// and lightness, so for a grey seed every color is that same grey.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// use color_changer::scheme::SchemeKind;
+    /// let red = RGB::new(0xFF, 0, 0);
+    /// assert_eq!(red.generate_scheme(SchemeKind::Complementary), vec![red, RGB::new(0, 0xFF, 0xFF)]);
+    /// ```
+    pub fn generate_scheme(&self, kind: SchemeKind) -> Vec<RGB> {
+        let [h, s, l] = rgb_to_hsl(*self);
+        let rotate = |degrees: &[f64]| -> Vec<RGB> {
+            degrees.iter().map(|dh| hsl_to_rgb([h + dh, s, l])).collect()
+        };
+        let others = match kind {
+            SchemeKind::Monochromatic => [-0.3, -0.15, 0.15, 0.3].iter()
+                .map(|dl| hsl_to_rgb([h, s, (l + dl).clamp(0.05, 0.95)]))
+                .collect(),
+            SchemeKind::Complementary => rotate(&[180.0]),
+            SchemeKind::Triadic => rotate(&[120.0, 240.0]),
+            SchemeKind::Analogous => rotate(&[-30.0, 30.0]),
+            SchemeKind::Tetradic => rotate(&[90.0, 180.0, 270.0]),
+        };
+        std::iter::once(*self).chain(others).collect()
+    }
+
+    /// Produces `count` shades and tints of this color, from a dark shade to a light tint.
+    /// They are evenly spaced in CIELAB lightness, between L* 15 and 95, so the steps look
+    /// uniform. Each keeps this color's hue, with the chroma fading towards the ends of the ramp
+    /// as it would when mixing with black or white. A single color sits halfway between the ends.
+    /// # Examples
+    /// ```
+    /// use color_changer::rgb::RGB;
+    /// let ramp = RGB::new(0x33, 0x66, 0x99).mono_ramp(5);
+    /// assert_eq!(ramp.len(), 5);
+    /// assert!(ramp[0].relative_luminance() < ramp[4].relative_luminance());
+    /// ```
+    pub fn mono_ramp(&self, count: usize) -> Vec<RGB> {
+        let (darkest, lightest) = RAMP_LIGHTNESS;
+        let [seed_l, a, b] = rgb_to_lab(*self);
+        (0..count)
+            .map(|i| {
+                let t = if count == 1 { 0.5 } else { i as f64 / (count - 1) as f64 };
+                let l = darkest + t * (lightest - darkest);
+                let towards_end = if l > seed_l {
+                    (l - seed_l) / (lightest - seed_l)
+                } else if l < seed_l {
+                    (seed_l - l) / (seed_l - darkest)
+                } else {
+                    0.0
+                };
+                let chroma = 1.0 - towards_end * (1.0 - RAMP_END_CHROMA);
+                lab_to_rgb([l, a * chroma, b * chroma])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hue_of(c: RGB) -> f64 {
+        rgb_to_hsl(c)[0]
+    }
+
+    #[test]
+    fn schemes() {
+        let seed = RGB::new(0x33, 0x66, 0xCC);
+        let mono = seed.generate_scheme(SchemeKind::Monochromatic);
+        assert_eq!(mono.len(), 5);
+        assert_eq!(mono[0], seed);
+        for c in &mono {
+            assert!((hue_of(*c) - hue_of(seed)).abs() < 2.0, "{:?}", c);
+        }
+        assert!(mono[1].relative_luminance() < mono[2].relative_luminance());
+        assert!(mono[3].relative_luminance() > seed.relative_luminance());
+
+        let triadic = seed.generate_scheme(SchemeKind::Triadic);
+        assert_eq!(triadic.len(), 3);
+        let hues: Vec<f64> = triadic.iter().map(|&c| hue_of(c)).collect();
+        for (i, a) in hues.iter().enumerate() {
+            for b in &hues[i + 1..] {
+                let d = (a - b).rem_euclid(360.0);
+                assert!((d - 120.0).abs() < 2.0 || (d - 240.0).abs() < 2.0, "{:?}", hues);
+            }
+        }
+
+        assert_eq!(seed.generate_scheme(SchemeKind::Analogous).len(), 3);
+        assert_eq!(seed.generate_scheme(SchemeKind::Tetradic).len(), 4);
+        let grey = RGB::new(0x80, 0x80, 0x80);
+        assert_eq!(grey.generate_scheme(SchemeKind::Triadic), vec![grey; 3]);
+    }
+
+    #[test]
+    fn mono_ramp() {
+        for &seed in &[RGB::new(0x33, 0x66, 0x99), RGB::new(0xFF, 0, 0), RGB::new(0x80, 0x80, 0x80)] {
+            let ramp = seed.mono_ramp(7);
+            assert_eq!(ramp.len(), 7);
+            for w in ramp.windows(2) {
+                assert!(w[0].relative_luminance() < w[1].relative_luminance(), "{:?}", ramp);
+            }
+            assert!(ramp[0].relative_luminance() < 0.05, "{}", ramp[0]);
+            assert!(ramp[6].relative_luminance() > 0.8, "{}", ramp[6]);
+        }
+        let blue = RGB::new(0x33, 0x66, 0x99);
+        assert!(blue.mono_ramp(5).iter().all(|c| c.b >= c.r));
+        assert!(blue.mono_ramp(0).is_empty());
+        assert_eq!(blue.mono_ramp(1).len(), 1);
+    }
+}
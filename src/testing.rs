@@ -0,0 +1,54 @@
+//! Helpers for comparing pixel buffers in tests. Available to downstream crates through the
+//! `testing` feature.
+
+use crate::rgb::RGB;
+
+/// Returns the index of the first pixel where `a` and `b` differ by more than `tolerance` in any
+/// channel, or `None` if every pixel is within tolerance.
+/// If the slices have different lengths, the first index past the end of the shorter one is reported.
+/// # Examples
+/// ```
+/// use color_changer::rgb::RGB;
+/// use color_changer::testing::first_mismatch;
+/// let a = [RGB::BLACK, RGB::WHITE];
+/// let b = [RGB::BLACK, RGB::BLACK];
+/// assert_eq!(first_mismatch(&a, &b, 0), Some(1));
+/// ```
+pub fn first_mismatch(a: &[RGB], b: &[RGB], tolerance: u8) -> Option<usize> {
+    let mismatch = a.iter()
+        .zip(b.iter())
+        .position(|(x, y)| {
+            x.as_parts().iter()
+                .zip(y.as_parts().iter())
+                .any(|(p, q)| p.abs_diff(*q) > tolerance)
+        });
+    match mismatch {
+        Some(i) => Some(i),
+        None if a.len() != b.len() => Some(a.len().min(b.len())),
+        None => None,
+    }
+}
+
+/// Returns whether `a` and `b` have the same length and every pair of pixels is within
+/// `tolerance` in each channel. Use [first_mismatch] to find where they differ.
+pub fn colors_within(a: &[RGB], b: &[RGB], tolerance: u8) -> bool {
+    first_mismatch(a, b, tolerance).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comparisons() {
+        let a = [RGB::new(10, 20, 30), RGB::new(200, 100, 0), RGB::WHITE];
+        assert!(colors_within(&a, &a, 0));
+
+        let b = [RGB::new(11, 19, 30), RGB::new(200, 101, 1), RGB::new(254, 255, 255)];
+        assert!(!colors_within(&a, &b, 0));
+        assert_eq!(first_mismatch(&a, &b, 0), Some(0));
+        assert!(colors_within(&a, &b, 1));
+
+        assert_eq!(first_mismatch(&a, &a[..2], 255), Some(2));
+    }
+}